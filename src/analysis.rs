@@ -0,0 +1,101 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::process::{CaseFolding, DEFAULT_NAMESPACE};
+
+/// Index of coincidence of english text, for comparison.
+pub const ENGLISH_IC: f64 = 0.0667;
+
+/// Frequency analysis of a text.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Analysis {
+	/// Number of characters of the text inside the namespace, the only ones
+	/// analyzed
+	pub length: usize,
+	/// Probability of two characters picked at random being the same one
+	pub index_of_coincidence: f64,
+	/// Characters of the text with their number of occurrences, the most
+	/// frequent first
+	pub characters: Vec<Frequency>,
+	/// Length of the counted n-grams
+	pub n: usize,
+	/// Overlapping n-grams of the text with their number of occurrences, the
+	/// most frequent first
+	pub ngrams: Vec<Frequency>,
+}
+
+/// Number of occurrences of a character or n-gram.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Frequency {
+	pub text: String,
+	pub count: usize,
+}
+
+impl Analysis {
+	/// Serializes the analysis as pretty printed JSON.
+	pub fn to_json(&self) -> Result<String> {
+		serde_json::to_string_pretty(self).map_err(|e| e.to_string().into())
+	}
+}
+
+/// Analyzes the characters of the given `text` inside the `namespace`, or the
+/// default one, counting them and their overlapping n-grams of length `n`.
+/// The `top` most frequent characters and n-grams are kept.
+pub fn analyze(text: &str, namespace: Option<&str>, n: usize, top: usize) -> Result<Analysis> {
+	if n == 0 {
+		return Err("the n-grams must have at least 1 character".into());
+	}
+	let namespace: HashSet<char> = namespace.map_or(DEFAULT_NAMESPACE.iter().copied().collect(), |ns| ns.chars().collect());
+	let chars: Vec<char> = CaseFolding::default()
+		.apply(text, &namespace)
+		.chars()
+		.filter(|c| namespace.contains(c))
+		.collect();
+
+	let characters = ranked(chars.iter().map(char::to_string));
+	let length = chars.len();
+	let coincidences: usize = characters.iter().map(|f| f.count * f.count.saturating_sub(1)).sum();
+	let index_of_coincidence = if length < 2 {
+		0.0
+	} else {
+		coincidences as f64 / (length * (length - 1)) as f64
+	};
+
+	Ok(Analysis {
+		length,
+		index_of_coincidence,
+		characters: characters.into_iter().take(top).collect(),
+		n,
+		ngrams: ranked(chars.windows(n).map(|w| w.iter().collect())).into_iter().take(top).collect(),
+	})
+}
+
+/// Counts the given `items`, sorting them by their count and then by
+/// themselves.
+fn ranked(items: impl Iterator<Item = String>) -> Vec<Frequency> {
+	let mut counts: HashMap<String, usize> = HashMap::new();
+	for item in items {
+		*counts.entry(item).or_default() += 1;
+	}
+
+	let mut counts: Vec<_> = counts.into_iter().map(|(text, count)| Frequency { text, count }).collect();
+	counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.text.cmp(&b.text)));
+	counts
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn texts_are_analyzed() {
+		let analysis = analyze("abab c!", None, 2, 2).unwrap();
+		assert_eq!(analysis.length, 5);
+		assert_eq!(analysis.index_of_coincidence, 0.2);
+		assert_eq!((analysis.characters[0].text.as_str(), analysis.characters[0].count), ("A", 2));
+		assert_eq!((analysis.ngrams[0].text.as_str(), analysis.ngrams[0].count), ("AB", 2));
+		assert!(analyze("AB", None, 0, 2).is_err());
+	}
+}
@@ -0,0 +1,376 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use fancy_regex::Regex;
+use tar::{Archive, Builder, EntryType, Header};
+use tracing::debug;
+
+use crate::error::{Error, Result};
+use crate::input::{OutputOpts, SourceOpts};
+use crate::process::FILENAME_NAMESPACE;
+use crate::{i18n, interrupt, jobs, output, source};
+
+/// Largest number of results of an archive's files kept to be reused by the
+/// files with the same contents.
+const CACHE_SIZE: usize = 256;
+
+/// Transformation of the names of an archive's entries.
+pub type Rename<'a> = &'a mut dyn FnMut(&str) -> Result<String>;
+
+/// Processes every file of the tar archive at `input` with the given
+/// `process`, writing the results to a new archive at `output` with the same
+/// structure, sorted by their paths. The files are decoded and the results encoded as told by the
+/// `source` and `output` options; entries other than files, like directories
+/// and links, are copied as they are. Gzip compressed archives are read and
+/// written transparently. A directory given as the `input` is processed as
+/// the archive of its contents, and the results are written into the
+/// directory at `output` instead. If a `rename` is given, the names of the entries
+/// are transformed with it as [`transform_name`] tells. The hidden and
+/// excluded entries are copied as they are, and the links to files are
+/// replaced with their processed targets if they are followed, as the
+/// `source` options tell. The files are processed at once on as many threads
+/// as the `source` jobs. Returns the number of processed files.
+pub fn transform(
+	input: &Path,
+	output: &Path,
+	source: &SourceOpts,
+	opts: &OutputOpts,
+	process: impl Fn(&str) -> Result<String> + Sync,
+	mut rename: Option<(usize, Rename<'_>)>
+) -> Result<usize> {
+	let read_error = |e: std::io::Error| format!("the archive '{}' cannot be read: {e}", input.display());
+
+	let directory = input.is_dir();
+	if directory && output.starts_with(input) {
+		return Err("the output directory cannot be inside the source one".into());
+	}
+	let bytes = if directory { pack(input).map_err(read_error)? } else { fs::read(input).map_err(read_error)? };
+	let bytes = if !directory && source::is_gzip(input, &bytes) {
+		source::gunzip(&bytes).map_err(read_error)?
+	} else {
+		bytes
+	};
+
+	// the entries are loaded up front, so the links can be followed to any
+	// of them
+	let mut archive = Archive::new(&bytes[..]);
+	let mut entries = Vec::new();
+	for entry in archive.entries().map_err(read_error)? {
+		let mut entry = entry.map_err(read_error)?;
+		let path = entry.path().map_err(read_error)?.into_owned();
+		let link = entry.link_name().map_err(read_error)?.map(|link| link.into_owned());
+		let header = entry.header().clone();
+		let mut contents = Vec::new();
+		std::io::Read::read_to_end(&mut entry, &mut contents).map_err(read_error)?;
+		entries.push(Item { path, link, header, contents });
+	}
+	// the entries are written sorted by their paths, which keeps every
+	// directory before its contents
+	entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+	// the files to process are picked first, so they are processed at once
+	let exclude = source.exclude
+		.iter()
+		.map(|glob| glob_regex(glob))
+		.collect::<Result<Vec<_>>>()?;
+	let files = entries
+		.iter()
+		.map(|item| {
+			// links are followed to the file they end at, if it is in the archive
+			let file = match item.header.entry_type() {
+				EntryType::Regular => Some(item),
+				EntryType::Symlink if source.follow_symlinks => {
+					resolve(&entries, item)?.filter(|target| target.header.entry_type() == EntryType::Regular)
+				},
+				_ => None,
+			};
+			let skipped = (!source.hidden && is_hidden(&item.path)) || is_excluded(&exclude, &item.path);
+			Ok(file.filter(|_| !skipped))
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	let pending: Vec<_> = entries.iter().zip(&files).filter_map(|(item, file)| Some((item, (*file)?))).collect();
+	let count = source.jobs.unwrap_or_else(jobs::default_count);
+	// the files with the same contents as an already processed one take its
+	// result
+	let cache = Mutex::new(HashMap::new());
+	let mut results = jobs::run(&pending, count, |&(item, file)| -> Result<_> {
+		if let Some(contents) = cache.lock().unwrap().get(&file.contents[..]) {
+			debug!(path = %item.path.display(), "reusing the result of an identical archive file");
+			return Ok(Vec::clone(contents));
+		}
+
+		debug!(path = %item.path.display(), "processing an archive file");
+		let text = source::decode(&file.contents, source.encoding, source.keep_bom)
+			.map_err(|encoding| format!("'{}' is not valid {}", item.path.display(), encoding.name()))?;
+		let result = process(&text)
+			.map_err(|e| format!("'{}' cannot be processed: {}", item.path.display(), i18n::tr_error(&e)))?;
+		let contents = output::encode(&result, opts.output_encoding, opts.output_newlines)?;
+
+		let mut cache = cache.lock().unwrap();
+		if cache.len() < CACHE_SIZE {
+			cache.insert(&file.contents[..], contents.clone());
+		}
+		Ok(contents)
+	}).into_iter();
+	// an interrupted run leaves no partial archive behind
+	if interrupt::interrupted() {
+		return Err(Error::Interrupted);
+	}
+
+	let mut builder = Builder::new(Vec::new());
+	let mut processed = 0;
+	for (item, file) in entries.iter().zip(files) {
+		let path = &item.path;
+		let mut header = item.header.clone();
+		let new_path = match rename.as_mut() {
+			Some((dimension, rename)) => transform_path(path, *dimension, rename)?,
+			None => path.clone(),
+		};
+
+		let Some((file, contents)) = file.and_then(|file| Some((file, results.next().flatten()?))) else {
+			debug!(path = %path.display(), "copying an archive entry");
+			let append = match (&item.link, rename.as_mut()) {
+				(Some(link), Some((dimension, rename))) => {
+					let link = transform_path(link, *dimension, rename)?;
+					builder.append_link(&mut header, &new_path, link)
+				},
+				_ => builder.append_data(&mut header, &new_path, &item.contents[..]),
+			};
+			append.map_err(|e| e.to_string())?;
+			continue;
+		};
+		let contents = contents?;
+
+		// a followed link takes the place of the file it points to
+		if !std::ptr::eq(file, item) {
+			header = file.header.clone();
+			header.set_mtime(item.header.mtime().unwrap_or_default());
+		}
+		header.set_size(contents.len() as u64);
+		builder.append_data(&mut header, &new_path, &contents[..]).map_err(|e| e.to_string())?;
+		processed += 1;
+	}
+
+	let bytes = builder.into_inner().map_err(|e| e.to_string())?;
+	if directory {
+		debug!(path = %output.display(), "unpacking the processed files");
+		return fs::create_dir_all(output)
+			.and_then(|_| Archive::new(&bytes[..]).unpack(output))
+			.map(|_| processed)
+			.map_err(|e| format!("the directory '{}' cannot be written: {e}", output.display()).into());
+	}
+	let gzip = opts.gzip_output || output.extension().is_some_and(|ext| ext == "gz" || ext == "tgz");
+	let bytes = if gzip { output::gzip(&bytes)? } else { bytes };
+	output::write_atomic(output, &bytes, None)
+		.map_err(|e| format!("the archive '{}' cannot be written: {e}", output.display()))?;
+
+	Ok(processed)
+}
+
+/// Packs the contents of the directory at `dir` into a tar archive, with the
+/// paths relative to it and the links kept as links.
+fn pack(dir: &Path) -> std::io::Result<Vec<u8>> {
+	debug!(path = %dir.display(), "packing the source directory");
+	let mut builder = Builder::new(Vec::new());
+	builder.follow_symlinks(false);
+	builder.append_dir_all("", dir)?;
+	builder.into_inner()
+}
+
+/// An entry of an archive, loaded into memory.
+struct Item {
+	path: PathBuf,
+	link: Option<PathBuf>,
+	header: Header,
+	contents: Vec<u8>,
+}
+
+/// Follows the chain of symbolic links starting at the given `item` up to the
+/// entry of `entries` it ends at. Links ending out of the archive give none,
+/// and chains going back to any of their links are reported.
+fn resolve<'a>(entries: &'a [Item], item: &'a Item) -> Result<Option<&'a Item>> {
+	let mut visited = HashSet::new();
+	let mut current = item;
+	while current.header.entry_type() == EntryType::Symlink {
+		if !visited.insert(&current.path) {
+			return Err(format!("the link '{}' is part of a loop", item.path.display()).into());
+		}
+		let Some(target) = current.link.as_ref().and_then(|link| {
+			normalize(&current.path.parent().unwrap_or(Path::new("")).join(link))
+		}) else {
+			return Ok(None);
+		};
+		match entries.iter().find(|entry| normalize(&entry.path).as_ref() == Some(&target)) {
+			Some(entry) => current = entry,
+			None => return Ok(None),
+		}
+	}
+
+	Ok(Some(current))
+}
+
+/// Resolves the `.` and `..` components of the given relative `path`, giving
+/// none if it is absolute or goes above its root.
+fn normalize(path: &Path) -> Option<PathBuf> {
+	let mut normalized = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::Normal(name) => normalized.push(name),
+			Component::CurDir => (),
+			Component::ParentDir => {
+				if !normalized.pop() {
+					return None;
+				}
+			},
+			Component::RootDir | Component::Prefix(_) => return None,
+		}
+	}
+
+	Some(normalized)
+}
+
+/// Whether any of the names along the given `path` starts with a dot.
+fn is_hidden(path: &Path) -> bool {
+	path.components().any(|component| match component {
+		Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+		_ => false,
+	})
+}
+
+/// Whether the given `path`, or any of the names along it, matches one of
+/// the `exclude` globs.
+fn is_excluded(exclude: &[(Regex, bool)], path: &Path) -> bool {
+	let full = normalize(path).unwrap_or_else(|| path.to_owned());
+	let full = full.to_string_lossy();
+	exclude.iter().any(|(glob, whole_path)| {
+		if *whole_path {
+			glob.is_match(&full).unwrap_or(false)
+		} else {
+			path.components().any(|c| glob.is_match(&c.as_os_str().to_string_lossy()).unwrap_or(false))
+		}
+	})
+}
+
+/// Translates the given `glob` into a regex, where `**` matches anything, `*`
+/// anything but a `/` and `?` a single character but a `/`. Tells whether it
+/// is matched against the whole paths, if it has any `/`, or their names.
+fn glob_regex(glob: &str) -> Result<(Regex, bool)> {
+	let mut regex = String::from("^");
+	let mut chars = glob.trim_start_matches("./").chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'*' if chars.peek() == Some(&'*') => {
+				chars.next();
+				regex.push_str(".*");
+			},
+			'*' => regex.push_str("[^/]*"),
+			'?' => regex.push_str("[^/]"),
+			c => regex.push_str(&fancy_regex::escape(&c.to_string())),
+		}
+	}
+	regex.push('$');
+
+	let glob_regex = Regex::new(&regex).map_err(|e| format!("the glob '{glob}' is not valid: {e}"))?;
+	Ok((glob_regex, glob.contains('/')))
+}
+
+/// Transforms every name along the given `path` with [`transform_name`].
+fn transform_path(
+	path: &Path,
+	dimension: usize,
+	rename: Rename<'_>
+) -> Result<PathBuf> {
+	path.components()
+		.map(|component| match component {
+			Component::Normal(name) => {
+				let name = name
+					.to_str()
+					.ok_or_else(|| format!("the name '{}' is not valid UTF-8", name.to_string_lossy()))?;
+				transform_name(name, dimension, &mut *rename).map(PathBuf::from)
+			},
+			other => Ok(PathBuf::from(other.as_os_str())),
+		})
+		.collect()
+}
+
+/// Transforms the characters of the given file `name` that belong to the
+/// [`FILENAME_NAMESPACE`] with the given `rename`, leaving the rest, like the
+/// dots of the extensions, in place. Only the whole blocks of `dimension`
+/// characters are transformed, so no fill is needed and the names are
+/// restored exactly.
+pub fn transform_name(
+	name: &str,
+	dimension: usize,
+	rename: Rename<'_>
+) -> Result<String> {
+	let safe: String = name.chars().filter(|&c| FILENAME_NAMESPACE.contains(c)).collect();
+	let whole = safe.chars().count() / dimension.max(1) * dimension.max(1);
+	if whole == 0 {
+		return Ok(name.to_owned());
+	}
+
+	let blocks: String = safe.chars().take(whole).collect();
+	let mut transformed = rename(&blocks)?.chars().chain(safe.chars().skip(whole)).collect::<Vec<_>>().into_iter();
+	Ok(name
+		.chars()
+		.map(|c| if FILENAME_NAMESPACE.contains(c) { transformed.next().unwrap_or(c) } else { c })
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::process::ProcessorBuilder;
+
+	#[test]
+	fn file_names_round_trip_over_their_namespace() {
+		let process = |name: &str, cipher: bool| {
+			let processor = ProcessorBuilder::default()
+				.key("GYBNQKURP".to_owned())
+				.source(name.to_owned())
+				.fill_letter(None)
+				.namespace(Some(FILENAME_NAMESPACE.to_owned()))
+				.build()
+				.unwrap();
+			let report = if cipher { processor.cipher() } else { processor.decipher() };
+			report.map(|report| report.result_txt)
+		};
+
+		let ciphered = transform_name("notes-2024.md", 3, &mut |n| process(n, true)).unwrap();
+		assert_ne!(ciphered, "notes-2024.md");
+		assert_eq!(ciphered.chars().nth(10), Some('.'));
+		let deciphered = transform_name(&ciphered, 3, &mut |n| process(n, false)).unwrap();
+		assert_eq!(deciphered, "notes-2024.md");
+	}
+
+	#[test]
+	fn directories_are_processed_into_directories() {
+		let root = std::env::temp_dir().join(format!("hill_cypher_archive_{}", std::process::id()));
+		let (input, output) = (root.join("in"), root.join("out"));
+		fs::create_dir_all(input.join("sub")).unwrap();
+		fs::write(input.join("a.txt"), "ACT").unwrap();
+		fs::write(input.join("sub").join("b.txt"), "ACT").unwrap();
+		fs::write(input.join(".hidden"), "ACT").unwrap();
+
+		let processed = transform(
+			&input,
+			&output,
+			&SourceOpts::default(),
+			&OutputOpts::default(),
+			|text| Ok(text.to_lowercase()),
+			None
+		);
+		let read = |path: &str| fs::read_to_string(output.join(path)).unwrap();
+		let contents = (read("a.txt"), read("sub/b.txt"), read(".hidden"));
+		let (source, opts) = (SourceOpts::default(), OutputOpts::default());
+		let nested = transform(&input, &input.join("out"), &source, &opts, |text| Ok(text.into()), None);
+		fs::remove_dir_all(&root).unwrap();
+
+		assert_eq!(processed.unwrap(), 2);
+		assert_eq!(contents, ("act".to_owned(), "act".to_owned(), "ACT".to_owned()));
+		assert!(nested.is_err());
+	}
+}
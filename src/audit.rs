@@ -0,0 +1,187 @@
+use std::fs::{self, OpenOptions};
+use std::io::{Read as _, Write as _};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use hmac::{Hmac, KeyInit, Mac};
+use rand::RngCore as _;
+use serde_json::json;
+use sha2::Sha256;
+use tracing::debug;
+
+use crate::completions;
+use crate::config;
+use crate::error::{Error, Result};
+use crate::i18n;
+use crate::process::Report;
+
+/// Destination of the audit records.
+#[derive(Debug, Clone)]
+pub enum AuditSink {
+	/// A file the records are appended to as JSON lines
+	File(PathBuf),
+	/// The local syslog daemon
+	Syslog,
+}
+
+impl FromStr for AuditSink {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		Ok(match s {
+			"syslog" => AuditSink::Syslog,
+			path => AuditSink::File(path.into()),
+		})
+	}
+}
+
+/// Size of the secret the key fingerprints are computed with, in bytes.
+const SECRET_LEN: usize = 32;
+
+/// An audit record of a single `cipher` or `decipher` operation, however many
+/// texts or chunks it processes. The key itself is never recorded, only a
+/// fingerprint of it.
+#[derive(Debug)]
+pub struct AuditEntry {
+	timestamp: SystemTime,
+	operation: &'static str,
+	key_fingerprint: String,
+	/// Number of characters processed so far
+	input_size: AtomicUsize,
+	/// Why the first text that could not be processed failed, if any
+	error: Mutex<Option<String>>,
+}
+
+impl AuditEntry {
+	/// Starts the record of an `operation` performed with the given `key`.
+	pub fn new(operation: &'static str, key: &str) -> Result<Self> {
+		Ok(AuditEntry {
+			timestamp: SystemTime::now(),
+			operation,
+			key_fingerprint: fingerprint(key, &secret()?),
+			input_size: AtomicUsize::new(0),
+			error: Mutex::new(None),
+		})
+	}
+
+	/// Records a text of `size` characters processed by the operation, along
+	/// with the `outcome` of processing it.
+	pub fn record(&self, size: usize, outcome: &Result<Report>) {
+		self.input_size.fetch_add(size, Ordering::Relaxed);
+		if let Err(e) = outcome {
+			self.error.lock().unwrap().get_or_insert_with(|| i18n::tr_error(e));
+		}
+	}
+
+	/// Completes the record with the `outcome` of the whole operation, which
+	/// failed if any of its texts did, and writes it to the given `sink`.
+	pub fn finish(self, outcome: &Result<()>, sink: &AuditSink) -> Result<()> {
+		let error = self.error
+			.into_inner()
+			.unwrap()
+			.or_else(|| outcome.as_ref().err().map(i18n::tr_error));
+		let mut record = json!({
+			"timestamp": humantime::format_rfc3339_millis(self.timestamp).to_string(),
+			"operation": self.operation,
+			"key_fingerprint": self.key_fingerprint,
+			"input_size": self.input_size.into_inner(),
+			"outcome": if error.is_none() { "ok" } else { "error" },
+		});
+		if let Some(error) = error {
+			record["error"] = error.into();
+		}
+
+		match sink {
+			AuditSink::File(path) => {
+				let mut file = OpenOptions::new()
+					.create(true)
+					.append(true)
+					.open(path)
+					.map_err(|e| audit_error(&e))?;
+				writeln!(file, "{record}").map_err(|e| audit_error(&e))
+			},
+			AuditSink::Syslog => syslog(&record.to_string()),
+		}
+	}
+}
+
+/// Computes a short fingerprint that identifies a `key` without revealing it:
+/// an HMAC of the key keyed with the given `secret`, so the few keys of small
+/// dimensions cannot be tried one by one against it without the secret.
+pub fn fingerprint(key: &str, secret: &[u8]) -> String {
+	let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMACs take secrets of any length");
+	mac.update(key.as_bytes());
+	mac.finalize()
+		.into_bytes()
+		.iter()
+		.take(8)
+		.map(|b| format!("{b:02x}"))
+		.collect()
+}
+
+/// Retrieves the secret of this installation the key fingerprints are
+/// computed with, generating it the first time into a file only its owner can
+/// read.
+fn secret() -> Result<Vec<u8>> {
+	let path = secret_path().ok_or("no configuration directory is known to keep the audit secret in")?;
+	let secret_error = |e: std::io::Error| format!("the audit secret '{}' cannot be accessed: {e}", path.display());
+
+	let mut options = OpenOptions::new();
+	options.write(true).create_new(true);
+	#[cfg(unix)]
+	std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir).map_err(secret_error)?;
+	}
+	match options.open(&path) {
+		Ok(mut file) => {
+			debug!(path = %path.display(), "generating the audit secret");
+			let mut secret = vec![0; SECRET_LEN];
+			rand::rng().fill_bytes(&mut secret);
+			file.write_all(&secret).and_then(|_| file.sync_all()).map_err(secret_error)?;
+			Ok(secret)
+		},
+		// another run may have generated it already
+		Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+			let mut secret = Vec::new();
+			fs::File::open(&path).and_then(|mut file| file.read_to_end(&mut secret)).map_err(secret_error)?;
+			if secret.len() < SECRET_LEN {
+				return Err(format!("the audit secret '{}' is malformed", path.display()).into());
+			}
+			Ok(secret)
+		},
+		Err(e) => Err(secret_error(e).into()),
+	}
+}
+
+/// Path of the file holding the audit secret, inside the user's configuration
+/// directory.
+fn secret_path() -> Option<PathBuf> {
+	config::dir().map(|dir| dir.join("audit.secret"))
+}
+
+/// Sends a `message` to the local syslog daemon, with the `user` facility and
+/// the `info` severity.
+#[cfg(unix)]
+fn syslog(message: &str) -> Result<()> {
+	use std::os::unix::net::UnixDatagram;
+
+	let line = format!("<14>{}[{}]: {message}", completions::BIN_NAME, std::process::id());
+	UnixDatagram::unbound()
+		.and_then(|socket| socket.send_to(line.as_bytes(), "/dev/log"))
+		.map(|_| ())
+		.map_err(|e| audit_error(&e))
+}
+
+#[cfg(not(unix))]
+fn syslog(_: &str) -> Result<()> {
+	Err("the syslog audit log is only available on unix systems".into())
+}
+
+/// Builds the error returned when an audit record cannot be written.
+fn audit_error(err: &std::io::Error) -> Error {
+	format!("the audit record cannot be written: {err}").into()
+}
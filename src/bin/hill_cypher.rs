@@ -0,0 +1,811 @@
+use std::io::IsTerminal as _;
+
+use colored::Colorize as _;
+use structopt::clap::{Error as ClapError, ErrorKind};
+
+use hill_cypher::{
+	analysis, archive, audit, checkpoint, completions, config, crack, doctor, error, i18n, input, interrupt, jobs, key,
+	keyspace, nato, output, parts, process, progress, qr, repl, sample, session, source, stage, stego, table, tui, ui,
+	watch, wizard
+};
+
+use input::{Args, ColorChoice, Format, KeyOpts, OutputOpts, SourceOpts, Command::{Analyze, Batch, Cipher, Compare, Complete, Completions, Crack, Decipher, Doctor, Info, Join, Key, Keyspace, RandText, Repl, Tui}, KeyCommand, KeyspaceCommand};
+use audit::{AuditEntry, AuditSink};
+use checkpoint::Checkpoint;
+use config::Config;
+use error::Result;
+use session::Session;
+use i18n::{Lang, Msg};
+use key::KeyForm;
+use process::{Comparison, ProcessorBuilder, Report, ReportStyle, namespace_from};
+
+/// Environment variable the key is taken from if no other one is given.
+const KEY_VAR: &str = "HILL_CIPHER_KEY";
+
+/// Stand-in shown for a key not given as it is on the command line.
+const REDACTED: &str = "[redacted]";
+
+fn main() {
+	match app() {
+		Ok(_) => (),
+		Err(e) => {
+			let interrupted = matches!(e, error::Error::Interrupted);
+			ui::print_error(e);
+			std::process::exit(if interrupted { interrupt::EXIT_STATUS } else { 1 });
+		},
+	}
+}
+
+/// Runs the application.
+fn app() -> Result<()> {
+	let args: Args = structopt::StructOpt::from_args();
+
+	i18n::set_lang(args.lang.unwrap_or_else(Lang::detect));
+	ui::set_color(if args.plain { ColorChoice::Never } else { args.color });
+	let _profile = ui::init_logging(args.verbose, args.profile.as_deref())?;
+
+	// a single record covers the whole operation, written before any error it
+	// ended with is reported
+	let mut audit = None;
+	let outcome = operate(args, &mut audit);
+	match audit {
+		Some((sink, entry)) => {
+			let recorded = entry.finish(&outcome, &sink);
+			outcome.and(recorded)
+		},
+		None => outcome,
+	}
+}
+
+/// Runs the operation requested by the given `args`, starting its `audit`
+/// record once it is known to process texts with a key.
+fn operate(args: Args, audit: &mut Option<(AuditSink, AuditEntry)>) -> Result<()> {
+	// without a subcommand, the wizard is launched only if someone is there
+	// to answer it
+	let cmd = match args.cmd {
+		Some(cmd) => cmd,
+		None if std::io::stdin().is_terminal() => wizard::run()?,
+		None => ClapError::with_description(
+			"a subcommand is required when not running on a terminal",
+			ErrorKind::MissingSubcommand
+		).exit(),
+	};
+
+	let (operation, mut key, source, mut fill_letter, mut namespace, iterations, mut output) = match cmd {
+		Cipher { key, source, fill_letter, namespace, iterations, output } => {
+			("cipher", key, source, fill_letter, namespace, iterations, output)
+		},
+		Decipher { key, source, fill_letter, namespace, iterations, output } => {
+			("decipher", key, source, fill_letter, namespace, iterations, output)
+		},
+		Join { key, parts, fill_letter, namespace, output } => {
+			let texts = parts
+				.iter()
+				.map(|path| source::read(path, Default::default(), false))
+				.collect::<Result<Vec<_>>>()?;
+			let source = SourceOpts { source: vec![parts::join(&texts)?], ..Default::default() };
+			("decipher", key, source, fill_letter, namespace, std::num::NonZeroUsize::MIN, output)
+		},
+		Repl { key, fill_letter, namespace } => {
+			return repl::run(key, fill_letter, namespace);
+		},
+		Compare { expected, actual, block_size } => {
+			ui::print_comparison(&Comparison::new(&expected, &actual), block_size);
+			return Ok(());
+		},
+		RandText { length, namespace, english } => {
+			let namespace = namespace.as_deref().and_then(namespace_from);
+			let mut rng = sample::rng(args.seed);
+			let text = if english {
+				sample::english(length, namespace.as_deref(), &mut rng)?
+			} else {
+				sample::uniform(length, namespace.as_deref(), &mut rng)?
+			};
+			println!("{text}");
+			return Ok(());
+		},
+		Analyze { source, namespace, ngram, top, format } => {
+			let namespace = namespace.as_deref().and_then(namespace_from);
+			let analysis = analysis::analyze(&source, namespace.as_deref(), ngram, top)?;
+			match format {
+				Format::Json => println!("{}", analysis.to_json()?),
+				_ => ui::print_analysis(&analysis),
+			}
+			return Ok(());
+		},
+		Crack { source, namespace, brute_force, top } => {
+			if !brute_force {
+				return Err("a search must be chosen, --brute-force is the only one available".into());
+			}
+			let namespace = namespace.as_deref().and_then(namespace_from);
+			let candidates = crack::brute_force(&source, namespace.as_deref(), top)?;
+			ui::print_candidates(&candidates);
+			return Ok(());
+		},
+		Batch { mut key, input, column, result_column, decipher, mut fill_letter, mut namespace, delimiter, output } => {
+			if let Some(path) = key.config.take() {
+				Config::load(&path)?.apply(&mut key, &mut fill_letter, &mut namespace);
+			}
+			let processor = ProcessorBuilder::default()
+				.key(resolve_key(key)?)
+				.source(String::new())
+				.fill_letter(fill_letter)
+				.namespace(namespace.as_deref().and_then(namespace_from))
+				.max_key_dimension(args.max_key_dimension)
+				.build()
+				.unwrap();
+			let process = |text: &str| {
+				let processor = processor.with_source(text.to_owned());
+				let report = if decipher { processor.decipher() } else { processor.cipher() };
+				report.map(|report| report.result_txt)
+			};
+
+			let reader = std::fs::File::open(&input)
+				.map_err(|e| format!("the table '{}' cannot be read: {e}", input.display()))?;
+			let progress = progress::bytes(reader.metadata().map_or(0, |meta| meta.len()), args.quiet);
+			let reader = progress.wrap_read(reader);
+			let delimiter = match delimiter {
+				Some(c) => u8::try_from(c).ok().filter(u8::is_ascii).ok_or("the delimiter must be an ASCII character")?,
+				None => table::delimiter_of(&input),
+			};
+			let processed = match &output {
+				// the table is written whole or not at all, stopping at the
+				// next row when interrupted
+				Some(path) => {
+					interrupt::install();
+					let write_error = |e: std::io::Error| format!("the table '{}' cannot be written: {e}", path.display());
+					let mut writer = output::AtomicFile::create(path, None).map_err(write_error)?;
+					let processed = table::transform(reader, &mut writer, delimiter, &column, &result_column, process)?;
+					writer.commit().map_err(write_error)?;
+					processed
+				},
+				None => table::transform(reader, std::io::stdout(), delimiter, &column, &result_column, process)?,
+			};
+			progress.finish_and_clear();
+			eprintln!("{}{} {processed}", i18n::tr(Msg::ProcessedRows).green().bold(), ":".bold());
+			return Ok(());
+		},
+		Key { cmd: KeyCommand::Convert { key, namespace, from, to } } => {
+			let namespace = namespace.as_deref().and_then(namespace_from);
+			println!("{}", key::convert(&key, namespace.as_deref(), from, to)?);
+			return Ok(());
+		},
+		Key { cmd: KeyCommand::Generate { dimension, namespace, to } } => {
+			let namespace = namespace.as_deref().and_then(namespace_from);
+			let key = key::generate(dimension, namespace.as_deref(), &mut sample::rng(args.seed))?;
+			println!("{}", key::convert(&key, namespace.as_deref(), KeyForm::Letters, to)?);
+			return Ok(());
+		},
+		Keyspace { cmd: KeyspaceCommand::Enumerate { dimension, namespace, limit, determinant, format } } => {
+			let namespace = namespace.as_deref().and_then(namespace_from);
+			let entries = keyspace::enumerate(dimension, namespace.as_deref())?
+				.filter(|entry| determinant.is_none_or(|d| entry.determinant == d))
+				.take(limit.unwrap_or(usize::MAX));
+			return keyspace::print(entries, dimension, format);
+		},
+		Doctor => {
+			doctor::run();
+			return Ok(());
+		},
+		Info => {
+			ui::print_info();
+			return Ok(());
+		},
+		Tui => return tui::run(),
+		Completions { shell } => {
+			return Ok(completions::generate(shell, &mut std::io::stdout())
+				.map_err(|e| e.to_string())?);
+		},
+		Complete { kind } => {
+			return Ok(completions::complete(kind, &mut std::io::stdout())
+				.map_err(|e| e.to_string())?);
+		},
+	};
+
+	// a key not given as it is on the command line, like one taken from the
+	// password store, a file, a QR code, the environment, a config file or the
+	// prompt, is kept out of the outputs
+	let redacted = key.key.as_deref().is_none_or(|key| key.starts_with("pass:"));
+
+	// the settings not given are taken from the last session, and the ones in
+	// use are remembered for the next one
+	match &args.session {
+		Some(path) => {
+			if key.last {
+				Session::load(path)?.apply(&mut key, &mut fill_letter, &mut namespace, &mut output.format)?;
+			}
+			Session::new(&key, fill_letter, namespace.as_deref(), output.format).save(path)?;
+		},
+		None if key.last => return Err("--last needs the --session file the settings were saved in".into()),
+		None => (),
+	}
+	// the ones still missing are taken from the config file, the default one
+	// only if it exists
+	let config = key.config.take().or_else(|| config::default_path().filter(|path| path.exists()));
+	if let Some(path) = config {
+		Config::load(&path)?.apply(&mut key, &mut fill_letter, &mut namespace);
+	}
+	// a namespace may be given by the name of a preset
+	let namespace = namespace.as_deref().and_then(namespace_from);
+
+	let key = resolve_key(key)?;
+	*audit = match &args.audit_log {
+		Some(sink) => Some((sink.clone(), AuditEntry::new(operation, &key)?)),
+		None => None,
+	};
+	let audit = audit.as_ref().map(|(_, entry)| entry);
+	if redacted && output.qr_key {
+		return Err("a redacted key cannot be rendered as a QR code".into());
+	}
+	let transcoding = match &output.output_namespace {
+		Some(alphabet) => Some(transcoding(namespace.as_deref(), alphabet)?),
+		None => None,
+	};
+	if let Some(len) = namespace.as_ref().map(|ns| ns.chars().count()).filter(|&len| len > args.max_namespace) {
+		return Err(format!(
+			"the namespace has {len} characters, over the limit of {}; raise --max-namespace",
+			args.max_namespace
+		).into());
+	}
+	// an automatic namespace is built out of the whole text it ciphers
+	let auto = match namespace.as_deref() {
+		Some("auto") => Some(None),
+		Some(ns) => ns.strip_prefix("auto+").map(|base| {
+			Some(namespace_from(base).unwrap_or_else(|| process::DEFAULT_NAMESPACE.iter().collect()))
+		}),
+		None => None,
+	};
+	// files too large to be read whole are streamed instead
+	let oversized = source.source_file.as_deref().is_some_and(|path| source::is_oversized(path, args.max_size.0));
+	let streamed = source.stream || oversized || source.source_file.as_deref().is_some_and(source::is_stream);
+	if auto.is_some() && operation != "cipher" {
+		return Err("an automatic namespace can only cipher; decipher with the namespace it reported".into());
+	}
+	if auto.is_some() && (transcoding.is_some() || source.archive.is_some() || source.checkpoint.is_some() || streamed) {
+		return Err("an automatic namespace needs a single whole source text, without --output-namespace".into());
+	}
+
+	let newlines = source.newlines;
+	let bad_blocks = source.bad_blocks;
+	let (normalization, case_folding) = (source.normalize, source.case_folding);
+	let threads = source.jobs.unwrap_or_default();
+	let processor = |namespace: Option<String>| ProcessorBuilder::default()
+		.key(key.clone())
+		.source(String::new())
+		.fill_letter(fill_letter)
+		.namespace(namespace)
+		.trace_blocks(output.dump || output.block_table)
+		.bad_blocks(bad_blocks)
+		.normalization(normalization)
+		.case_folding(case_folding)
+		.max_key_dimension(args.max_key_dimension)
+		.iterations(iterations.get())
+		.jobs(threads)
+		.build()
+		.unwrap();
+	// the key is set up once for every text, unless the namespace is built
+	// out of each of them
+	let shared = auto.is_none().then(|| processor(namespace.clone()));
+	let process = |text: &str| -> Result<Report> {
+		let (stripped, breaks) = source::split_lines(text, newlines);
+		let stripped = match &transcoding {
+			Some((math, alphabet)) if operation == "decipher" => process::transcode(&stripped, alphabet, math),
+			_ => stripped,
+		};
+		let processor = match &shared {
+			Some(shared) => shared.with_source(stripped),
+			None => {
+				let fill = fill_letter.map(String::from).unwrap_or_default();
+				let base = auto.as_ref().and_then(Option::as_deref);
+				processor(Some(process::auto_namespace(base, &[&key, &stripped, &fill]))).with_source(stripped)
+			},
+		};
+		let outcome = if operation == "cipher" {
+			processor.cipher()
+		} else {
+			processor.decipher()
+		};
+
+		// the line terminators kept out of the process are put back in place
+		let outcome = outcome.map(|report| Report {
+			result_txt: match &transcoding {
+				Some((math, alphabet)) if operation == "cipher" => {
+					source::restore_lines(&process::transcode(&report.result_txt, math, alphabet), &breaks, newlines)
+				},
+				_ => source::restore_lines(&report.result_txt, &breaks, newlines),
+			},
+			source_txt: text.to_owned(),
+			used_key: if redacted { REDACTED.to_owned() } else { report.used_key },
+			effective_key: report.effective_key.map(|key| if redacted { REDACTED.to_owned() } else { key }),
+			..report
+		});
+
+		if let Some(entry) = audit {
+			entry.record(text.chars().count(), &outcome);
+		}
+		outcome
+	};
+
+	// the long runs below stop at their next safe point when interrupted
+	if let Some(path) = &source.archive {
+		interrupt::install();
+		// the names are processed over their own namespace
+		let mut rename = |name: &str| -> Result<String> {
+			let processor = ProcessorBuilder::default()
+				.key(key.clone())
+				.source(name.to_owned())
+				.fill_letter(None)
+				.namespace(Some(process::FILENAME_NAMESPACE.to_owned()))
+				.build()
+				.unwrap();
+			let report = if operation == "cipher" { processor.cipher() } else { processor.decipher() };
+			report
+				.map(|report| report.result_txt)
+				.map_err(|e| format!("the names cannot be processed: {}", i18n::tr_error(&e)).into())
+		};
+		let processed = archive::transform(
+			path,
+			output.output.as_ref().unwrap(),
+			&source,
+			&output,
+			|text| process(text).map(|report| report.result_txt),
+			source.cipher_names.then_some((process::key_dimension(&key), &mut rename as archive::Rename))
+		)?;
+		eprintln!("{}{} {processed}", i18n::tr(Msg::ProcessedFiles).green().bold(), ":".bold());
+		return Ok(());
+	}
+
+	// watched files are processed again on every change, each directory entry
+	// into an output file of the same name
+	if let (true, Some(path), Some(out_path)) = (source.watch, &source.source_file, &output.output) {
+		let directory = path.is_dir();
+		if directory {
+			if out_path.starts_with(path) {
+				return Err("the output directory cannot be inside the watched one".into());
+			}
+			std::fs::create_dir_all(out_path)
+				.map_err(|e| format!("the output directory '{}' cannot be created: {e}", out_path.display()))?;
+		}
+		interrupt::install();
+		return watch::run(path, |file| {
+			let text = source::read(file, source.encoding, source.keep_bom)?;
+			let result = process(&text)?.result_txt;
+			let target = match file.file_name().filter(|_| directory) {
+				Some(name) => out_path.join(name),
+				None => out_path.clone(),
+			};
+			output::write(&target, &result, output.output_encoding, output.output_newlines, output.gzip_output)?;
+			eprintln!("{} {} {}", file.display(), "->".bold(), target.display());
+			Ok(())
+		});
+	}
+
+	// the streamed source texts are never whole, so the options needing them
+	// are rejected rather than left out
+	let sliced = source.offset.is_some() || source.length.is_some();
+	let stream = source.source_file
+		.clone()
+		.filter(|path| !sliced && !source.lines && (source.stream || oversized || source::is_stream(path)));
+	let streamed_run = source.checkpoint.is_some() || stream.is_some();
+	if let Some(option) = whole_text_option(&source, &output).filter(|_| streamed_run) {
+		let why = if oversized && !source.stream { ", but it is streamed for being over --max-size" } else { "" };
+		return Err(format!("{option} needs the whole source text{why}").into());
+	}
+
+	// checkpointed runs are processed as a stream, recording their progress
+	// after every processed chunk
+	if let (Some(state), Some(path), Some(out_path)) = (&source.checkpoint, &source.source_file, &output.output) {
+		interrupt::install();
+		let dimension = process::key_dimension(&key);
+		let mut checkpoint = Checkpoint::load(state, path)?;
+		let mut out = output::Sink::resume(
+			out_path,
+			checkpoint.output_len,
+			output.output_encoding,
+			output.output_newlines
+		)?;
+
+		let mut slicer = source::Slicer::new(newlines, dimension, checkpoint.blocks, None);
+		let progress = progress::bytes(file_len(path), args.quiet);
+		source::stream(path, &source, dimension, |chunk| {
+			if interrupt::interrupted() {
+				return Ok(false);
+			}
+			progress.inc(chunk.len() as u64);
+			slicer.push(chunk);
+			let text = std::mem::take(&mut slicer.text);
+			let (stripped, _) = source::split_lines(&text, newlines);
+			if stripped.is_empty() {
+				return Ok(true);
+			}
+
+			out.write(&process(&text)?.result_txt)?;
+			checkpoint.blocks += stripped.chars().count().div_ceil(dimension);
+			checkpoint.output_len = out.written();
+			checkpoint.save(state)?;
+			Ok(true)
+		})?;
+		progress.finish_and_clear();
+
+		out.finish()?;
+		if interrupt::interrupted() {
+			return Err(error::Error::Interrupted);
+		}
+		return Checkpoint::remove(state);
+	}
+
+	// streams are processed as their data arrives, printing only the results
+	if let Some(path) = &stream {
+		let mut out = output::Sink::open(
+			output.output.as_deref(),
+			output.output_encoding,
+			output.output_newlines,
+			output.gzip_output
+		)?;
+		interrupt::install();
+		let progress = progress::bytes(file_len(path), args.quiet);
+		source::stream(path, &source, process::key_dimension(&key), |chunk| {
+			progress.inc(chunk.len() as u64);
+			out.write(&process(chunk)?.result_txt).map(|_| !interrupt::interrupted())
+		})?;
+		progress.finish_and_clear();
+		// an interrupted output is dropped, leaving the file as it was
+		if interrupt::interrupted() {
+			return Err(error::Error::Interrupted);
+		}
+		return out.finish();
+	}
+
+	// several source texts are processed at once into a single report
+	if source.source.len() > 1 || source.lines {
+		if let Some(option) = single_text_option(&source, &output) {
+			return Err(format!("{option} takes a single source text").into());
+		}
+		// the files are only written once every text is processed
+		if output.output.is_some() || output.output_report.is_some() {
+			interrupt::install();
+		}
+		let count = source.jobs.unwrap_or_else(jobs::default_count);
+		let texts: Vec<String> = if source.lines {
+			let text = resolve_source(source, process::key_dimension(&key), args.max_size.0)?;
+			text.lines().filter(|line| !line.is_empty()).map(str::to_owned).collect()
+		} else {
+			source.source
+		};
+		let progress = progress::items(texts.len() as u64, args.quiet);
+		let outcomes = jobs::run(&texts, count, |text| {
+			let outcome = process(text);
+			progress.inc(1);
+			outcome
+		});
+		let mut outcomes: Vec<_> = texts
+			.into_iter()
+			.zip(outcomes)
+			.map(|(text, outcome)| (text, outcome.unwrap_or(Err(error::Error::Interrupted))))
+			.collect();
+		progress.finish_and_clear();
+		if interrupt::interrupted() {
+			return Err(error::Error::Interrupted);
+		}
+
+		match output.format {
+			// a script gets no partial results
+			_ if output.raw => {
+				if let Some(failed) = outcomes.iter().position(|(_, outcome)| outcome.is_err()) {
+					return outcomes.swap_remove(failed).1.map(drop);
+				}
+				outcomes.iter().flat_map(|(_, outcome)| outcome).for_each(|report| ui::print_raw(&report.result_txt));
+			},
+			Format::Csv | Format::Tsv => {
+				let items: Vec<_> = outcomes.iter().map(|(input, outcome)| (input.clone(), outcome)).collect();
+				ui::print_table(&items, if output.format == Format::Csv { b',' } else { b'\t' })?;
+			},
+			_ => (),
+		}
+
+		// the output file holds the result texts one per line, with none
+		// missing
+		let failed = outcomes.iter().filter(|(_, outcome)| outcome.is_err()).count();
+		if let Some(path) = output.output.as_ref().filter(|_| failed == 0) {
+			let results: String = outcomes
+				.iter()
+				.flat_map(|(_, outcome)| outcome)
+				.map(|report| report.result_txt.trim_end_matches('\n').to_owned() + "\n")
+				.collect();
+			output::write(path, &results, output.output_encoding, output.output_newlines, output.gzip_output)?;
+		}
+
+		let batch = process::Batch::new(outcomes);
+		match output.format {
+			_ if output.raw => (),
+			Format::Json => println!("{}", batch.to_json()?),
+			Format::Yaml => print!("{}", batch.to_yaml()?),
+			Format::Toml => print!("{}", batch.to_toml()?),
+			Format::Csv | Format::Tsv => (),
+			_ => ui::print_batch(&batch),
+		}
+		if let Some(path) = &output.output_report {
+			output::write_batch_report(path, &batch)?;
+		}
+		if let Some(path) = output.output.as_ref().filter(|_| failed > 0) {
+			return Err(format!("{failed} of the source texts failed, so '{}' is not written", path.display()).into());
+		}
+		return Ok(());
+	}
+
+	let (extract_stego, spelled) = (source.extract_stego, source.nato);
+	let replaced = source.source_file.clone().filter(|_| source.in_place);
+	let backup = !source.no_backup;
+	let source_file = source.source_file.as_ref().map(|path| path.display().to_string());
+	let text = resolve_source(source, process::key_dimension(&key), args.max_size.0)?;
+	let text = if extract_stego { stego::extract(&text)? } else { text };
+	let text = if spelled { nato::decode(&text)? } else { text };
+	// the files are only written once the whole text is processed, so an
+	// interruption leaves them as they were
+	let writes_files = output.output.is_some() || replaced.is_some() || output.output_report.is_some()
+		|| output.qr_output.is_some();
+	if writes_files {
+		interrupt::install();
+	}
+	let outcome = process(&text);
+	if interrupt::interrupted() {
+		return Err(error::Error::Interrupted);
+	}
+	let outcome = match &output.stego {
+		Some(path) => {
+			let cover = source::read(path, Default::default(), false)?;
+			outcome.and_then(|report| Ok(Report { result_txt: stego::hide(&report.result_txt, &cover)?, ..report }))
+		},
+		None => outcome,
+	};
+	let outcome = match output.part {
+		Some(part) => outcome.map(|report| Report {
+			result_txt: parts::with_header(part, &report.result_txt),
+			..report
+		}),
+		None => outcome,
+	};
+	// a table names its text by the file it was read from, if any
+	if let Format::Csv | Format::Tsv = output.format {
+		let input = source_file.unwrap_or_else(|| text.clone());
+		ui::print_table(&[(input, &outcome)], if output.format == Format::Csv { b',' } else { b'\t' })?;
+	}
+	let report = match outcome {
+		// the explanation leads the error, which is still reported by `main`
+		Err(e) if (args.explain_errors || args.verbose > 0) && auto.is_none() => {
+			ui::print_explanation(&process::explain(&e, &key, namespace.as_deref()));
+			return Err(e);
+		},
+		outcome => outcome?,
+	};
+
+	stage!("rendering", match output.format {
+		_ if output.raw => ui::print_raw(&report.result_txt),
+		Format::Text => {
+			let style = if output.compact { ReportStyle::Compact } else { ReportStyle::Verbose };
+			ui::print_report(&report, style, args.plain, output.output.as_deref().or(replaced.as_deref()));
+		},
+		Format::Json => println!("{}", report.to_json()?),
+		Format::Yaml => print!("{}", report.to_yaml()?),
+		Format::Toml => print!("{}", report.to_toml()?),
+		Format::Nato => println!("{}", nato::encode(&report.result_txt)),
+		Format::Csv | Format::Tsv => (),
+	});
+	if let Some(ns) = report.def_namespace.as_deref().filter(|_| auto.is_some()) {
+		eprintln!("{}{} {}", i18n::tr(Msg::GeneratedNamespace).yellow(), ":".bold(), input::escape(ns));
+	}
+	if output.format == Format::Text && !report.failed_blocks.is_empty() {
+		ui::print_failures(&report);
+	}
+	if output.diff {
+		ui::print_diff(&report);
+	}
+	if output.dump {
+		ui::print_dump(&report);
+	}
+	if output.block_table {
+		ui::print_block_table(&report);
+	}
+	if output.annotate {
+		// a transcoded ciphertext is annotated with the indices of its alphabet
+		let namespace = match &transcoding {
+			Some((_, alphabet)) if operation == "cipher" => alphabet.clone(),
+			_ => report.def_namespace.as_ref().map_or(process::DEFAULT_NAMESPACE.to_vec(), |ns| ns.chars().collect()),
+		};
+		ui::print_annotation(&report, &namespace);
+	}
+
+	if let Some(path) = &output.output {
+		output::write(
+			path,
+			&report.result_txt,
+			output.output_encoding,
+			output.output_newlines,
+			output.gzip_output
+		)?;
+	}
+	if let Some(path) = &replaced {
+		output::replace(
+			path,
+			&report.result_txt,
+			output.output_encoding,
+			output.output_newlines,
+			backup
+		)?;
+	}
+	if let Some(path) = &output.output_report {
+		output::write_report(path, &report)?;
+	}
+
+	// rendering the result text, or the used key, as a QR code
+	let qr_payload = if output.qr_key { &report.used_key } else { &report.result_txt };
+	if output.qr {
+		qr::print(qr_payload)?;
+	}
+	if let Some(path) = &output.qr_output {
+		qr::save(qr_payload, path)?;
+	}
+
+	Ok(())
+}
+
+/// Resolves the source text to process from the supplied source options,
+/// taking only the asked slice of blocks of `dimension` characters, if any.
+/// Whole source texts over `max_size` bytes are rejected before they are
+/// loaded, whenever their size is known beforehand.
+fn resolve_source(opts: SourceOpts, dimension: usize, max_size: u64) -> Result<String> {
+	let sliced = opts.offset.is_some() || opts.length.is_some();
+	let offset = opts.offset.unwrap_or_default();
+	if let Some(path) = opts.source_file.as_ref().filter(|_| sliced) {
+		return source::read_blocks(path, &opts, dimension, offset, opts.length);
+	}
+
+	let text = match (opts.source.into_iter().next(), opts.source_file, opts.source_url) {
+		(Some(source), _, _) if source == "-" => source::read_stdin(opts.encoding, opts.keep_bom)?,
+		(Some(source), _, _) => source,
+		(None, Some(path), _) => {
+			if let Ok(metadata) = std::fs::metadata(&path) {
+				check_size(metadata.len(), max_size)?;
+			}
+			source::read(&path, opts.encoding, opts.keep_bom)?
+		},
+		(None, None, Some(url)) => source::fetch(&url, opts.encoding, opts.keep_bom)?,
+		(None, None, None) => return Err("no source text was supplied".into()),
+	};
+	// compressed files and fetched bodies are only known once loaded
+	if !sliced {
+		check_size(text.len() as u64, max_size)?;
+		return Ok(text);
+	}
+
+	let mut slicer = source::Slicer::new(opts.newlines, dimension, offset, opts.length);
+	slicer.push(&text);
+	Ok(slicer.text)
+}
+
+/// Resolves the characters of the `namespace` the processes compute over,
+/// and of the `alphabet`, or name of a preset, the ciphertext is written in,
+/// checking that both have the same size.
+fn transcoding(namespace: Option<&str>, alphabet: &str) -> Result<(Vec<char>, Vec<char>)> {
+	let chars = |ns: Option<&str>| match ns {
+		Some(ns) => ns.chars().collect::<Vec<_>>(),
+		None => process::DEFAULT_NAMESPACE.to_vec(),
+	};
+	let math = chars(namespace);
+	let alphabet = chars(namespace_from(alphabet).as_deref());
+
+	if alphabet.len() != math.len() {
+		return Err(format!(
+			"the output namespace has {} characters, but the namespace has {}",
+			alphabet.len(),
+			math.len()
+		).into());
+	}
+	if alphabet.iter().collect::<std::collections::HashSet<_>>().len() != alphabet.len() {
+		return Err("the output namespace has duplicated characters".into());
+	}
+
+	Ok((math, alphabet))
+}
+
+/// Retrieves the first of the given options that need the whole source text,
+/// which streamed ones never are.
+fn whole_text_option(source: &SourceOpts, output: &OutputOpts) -> Option<&'static str> {
+	[
+		("--in-place", source.in_place),
+		("--format", output.format != Format::Text),
+		("--compact", output.compact),
+		("--diff", output.diff),
+		("--dump", output.dump),
+		("--annotate", output.annotate),
+		("--block-table", output.block_table),
+		("--output-report", output.output_report.is_some()),
+		("--stego", output.stego.is_some()),
+		("--part", output.part.is_some()),
+		("--qr", output.qr || output.qr_key || output.qr_output.is_some()),
+		("--extract-stego", source.extract_stego),
+		("--nato", source.nato),
+	]
+	.into_iter()
+	.find_map(|(option, given)| given.then_some(option))
+}
+
+/// Retrieves the first of the given options that apply to a single source
+/// text, which runs over many of them cannot take.
+fn single_text_option(source: &SourceOpts, output: &OutputOpts) -> Option<&'static str> {
+	[
+		("--in-place", source.in_place),
+		("--compact", output.compact),
+		("--diff", output.diff),
+		("--dump", output.dump),
+		("--annotate", output.annotate),
+		("--block-table", output.block_table),
+		("--stego", output.stego.is_some()),
+		("--part", output.part.is_some()),
+		("--qr", output.qr || output.qr_key || output.qr_output.is_some()),
+		("--extract-stego", source.extract_stego),
+		("--nato", source.nato),
+	]
+	.into_iter()
+	.find_map(|(option, given)| given.then_some(option))
+}
+
+/// Retrieves the size of the file at `path`, or 0 if unknown, like for a
+/// stream.
+fn file_len(path: &std::path::Path) -> u64 {
+	std::fs::metadata(path).map_or(0, |meta| meta.len())
+}
+
+/// Checks that a source text of `len` bytes is within the `max_size` limit.
+fn check_size(len: u64, max_size: u64) -> Result<()> {
+	if len <= max_size {
+		return Ok(());
+	}
+
+	Err(format!(
+		"the source text is too large ({len} bytes, over the limit of {max_size}); \
+		process it in parts with --offset and --length, stream it with --stream or --checkpoint, \
+		or raise --max-size"
+	).into())
+}
+
+/// Resolves the key to use from the supplied key options.
+fn resolve_key(opts: KeyOpts) -> Result<String> {
+	match (opts.key, opts.key_qr, opts.key_file, opts.key_env) {
+		(Some(key), _, _, _) => match key.strip_prefix("pass:") {
+			Some(entry) => pass_key(entry),
+			None => Ok(key),
+		},
+		(None, Some(path), _, _) => qr::read(&path),
+		(None, None, Some(path), _) => key::read(&path),
+		(None, None, None, Some(var)) => std::env::var(&var)
+			.map_err(|_| format!("the environment variable '{var}' holds no key").into()),
+		// the key is asked for only if someone is there to type it
+		(None, None, None, None) => match std::env::var(KEY_VAR) {
+			Ok(key) => Ok(key),
+			Err(_) if std::io::stdin().is_terminal() && std::io::stderr().is_terminal() => wizard::ask_hidden("Key"),
+			Err(_) => Err(format!(
+				"no key was supplied; give --key, --key-qr, --key-env, --key-file or set {KEY_VAR}"
+			).into()),
+		},
+	}
+}
+
+/// Fetches a key from the given `entry` of the standard Unix password store,
+/// taking the first line of the entry as the key, as `pass` does for passwords.
+fn pass_key(entry: &str) -> Result<String> {
+	let out = std::process::Command::new("pass")
+		.args(["show", entry])
+		.stderr(std::process::Stdio::inherit())
+		.output()
+		.map_err(|e| format!("the password store cannot be read, is `pass` installed? {e}"))?;
+	if !out.status.success() {
+		return Err(format!("the password store entry '{entry}' cannot be read").into());
+	}
+
+	String::from_utf8(out.stdout)
+		.ok()
+		.and_then(|secret| secret.lines().next().map(str::to_owned))
+		.filter(|key| !key.is_empty())
+		.ok_or_else(|| format!("the password store entry '{entry}' holds no key").into())
+}
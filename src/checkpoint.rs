@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::Result;
+use crate::output;
+
+/// Progress of a run over a source file, recorded so that an interrupted run
+/// can resume from it.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+	/// Source file being processed
+	pub source: PathBuf,
+	/// Number of blocks of the source text already processed
+	pub blocks: usize,
+	/// Number of bytes of the output already written
+	pub output_len: u64,
+}
+
+impl Checkpoint {
+	/// Loads the checkpoint at `path` of a run over the given `source` file,
+	/// starting from scratch if there is none yet.
+	pub fn load(path: &Path, source: &Path) -> Result<Self> {
+		let state = match fs::read_to_string(path) {
+			Ok(state) => state,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+				return Ok(Checkpoint { source: source.to_owned(), ..Default::default() });
+			},
+			Err(e) => return Err(checkpoint_error(path, &e)),
+		};
+
+		let checkpoint: Checkpoint = serde_json::from_str(&state)
+			.map_err(|e| format!("the checkpoint '{}' is malformed: {e}", path.display()))?;
+		if checkpoint.source != source {
+			return Err(format!(
+				"the checkpoint '{}' belongs to the source file '{}'",
+				path.display(),
+				checkpoint.source.display()
+			).into());
+		}
+		debug!(blocks = checkpoint.blocks, "resuming from the checkpoint");
+
+		Ok(checkpoint)
+	}
+
+	/// Records the checkpoint at `path`, never leaving it half written.
+	pub fn save(&self, path: &Path) -> Result<()> {
+		let state = serde_json::to_string(self).map_err(|e| e.to_string())?;
+		output::write_atomic(path, state.as_bytes(), None).map_err(|e| checkpoint_error(path, &e))
+	}
+
+	/// Removes the checkpoint at `path`, once the run is complete.
+	pub fn remove(path: &Path) -> Result<()> {
+		match fs::remove_file(path) {
+			Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(checkpoint_error(path, &e)),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Builds the error returned when a checkpoint cannot be read or written.
+fn checkpoint_error(path: &Path, err: &std::io::Error) -> crate::error::Error {
+	format!("the checkpoint '{}' cannot be accessed: {err}", path.display()).into()
+}
@@ -0,0 +1,54 @@
+use std::io::Write;
+
+use structopt::StructOpt as _;
+use structopt::clap::Shell;
+
+use crate::input::{Args, CompletionKind};
+use crate::process::NAMESPACE_PRESETS;
+
+/// Name of the binary the completion scripts are registered for, which is
+/// named after the package.
+pub const BIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// Writes the completion script for the given `shell` to `out`. Besides the
+/// static completion of commands and flags, the bash and fish scripts ask the
+/// binary itself for the values that can be completed dynamically, like the
+/// namespace presets.
+pub fn generate(shell: Shell, out: &mut impl Write) -> std::io::Result<()> {
+	Args::clap().gen_completions_to(BIN_NAME, shell, out);
+
+	match shell {
+		Shell::Bash => write!(out, r#"
+_{name}_dynamic() {{
+    case "${{COMP_WORDS[COMP_CWORD-1]}}" in
+        -n|--namespace)
+            COMPREPLY=( $(compgen -W "$({name} complete namespaces 2>/dev/null)" -- "${{COMP_WORDS[COMP_CWORD]}}") )
+            return 0
+            ;;
+    esac
+    _{name} "$@"
+}}
+
+complete -F _{name}_dynamic -o bashdefault -o default {name}
+"#, name = BIN_NAME),
+		Shell::Fish => writeln!(
+			out,
+			"complete -c {name} -s n -l namespace -x -a '({name} complete namespaces 2>/dev/null)'",
+			name = BIN_NAME
+		),
+		_ => Ok(()),
+	}
+}
+
+/// Prints the values that can be completed for the given `kind`, one per line.
+pub fn complete(kind: CompletionKind, out: &mut impl Write) -> std::io::Result<()> {
+	match kind {
+		CompletionKind::Namespaces => {
+			for (name, _) in NAMESPACE_PRESETS {
+				writeln!(out, "{name}")?;
+			}
+		},
+	}
+
+	Ok(())
+}
@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::completions;
+use crate::error::Result;
+use crate::input::KeyOpts;
+
+/// Directory of the program inside the user's configuration directory, which
+/// holds its config file and audit secret.
+pub fn dir() -> Option<PathBuf> {
+	let var = |name| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+	let dir = var("XDG_CONFIG_HOME")
+		.or_else(|| var("HOME").map(|home| home.join(".config")))
+		.or_else(|| var("APPDATA"))?;
+	Some(dir.join(completions::BIN_NAME))
+}
+
+/// Path of the config file read when none is given with --config.
+pub fn default_path() -> Option<PathBuf> {
+	dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Default settings of the ciphers and deciphers, read from a TOML file so
+/// they need not be given every time.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+	/// Key, which may be a `pass:<entry>` reference
+	pub key: Option<String>,
+	pub fill_letter: Option<char>,
+	pub namespace: Option<String>,
+}
+
+impl Config {
+	/// Loads the config file at `path`.
+	pub fn load(path: &Path) -> Result<Self> {
+		let text = fs::read_to_string(path)
+			.map_err(|e| format!("the config file '{}' cannot be read: {e}", path.display()))?;
+		Self::parse(&text).map_err(|e| format!("the config file '{}' is malformed: {e}", path.display()).into())
+	}
+
+	/// Parses the settings out of the given TOML `text`.
+	pub fn parse(text: &str) -> std::result::Result<Self, toml::de::Error> {
+		toml::from_str(text)
+	}
+
+	/// Fills the settings that were not given with the ones of the config,
+	/// so the given ones always take precedence.
+	pub fn apply(self, key: &mut KeyOpts, fill_letter: &mut Option<char>, namespace: &mut Option<String>) {
+		if !key.is_given() {
+			key.key = self.key;
+		}
+		*fill_letter = fill_letter.or(self.fill_letter);
+		if namespace.is_none() {
+			*namespace = self.namespace;
+		}
+		debug!(key_given = key.is_given(), "config settings applied");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn config_fills_the_settings_not_given() {
+		let config = Config::parse("key = \"GYBNQKURP\"\nfill-letter = \"X\"\nnamespace = \"ABCD\"").unwrap();
+		let (mut key, mut fill_letter, mut namespace) = (KeyOpts::default(), Some('Q'), None);
+		config.apply(&mut key, &mut fill_letter, &mut namespace);
+		assert_eq!(key.key.as_deref(), Some("GYBNQKURP"));
+		assert_eq!((fill_letter, namespace.as_deref()), (Some('Q'), Some("ABCD")));
+		assert!(Config::parse("keys = \"GYBNQKURP\"").is_err());
+	}
+}
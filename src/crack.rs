@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+
+use tracing::debug;
+
+use crate::error::{Error, Result};
+use crate::keyspace;
+use crate::matrix::ModMatrix;
+use crate::process::{CaseFolding, DEFAULT_NAMESPACE, suggest};
+use crate::sample::CORPUS;
+
+/// A key found by a search, along with the text it deciphers the ciphertext
+/// into and how much that text looks like english.
+#[derive(Debug, PartialEq)]
+pub struct Candidate {
+	pub key: String,
+	/// Sum of the log probabilities of the bigrams of the deciphered text
+	pub score: f64,
+	pub plaintext: String,
+}
+
+/// Tries every invertible 2x2 key of the given `namespace`, or of the default
+/// one, to decipher the given `ciphertext`, ranking the deciphered texts by
+/// their english bigram statistics. The `top` best candidates are retrieved,
+/// the most english-like first. Whitespace in the ciphertext is ignored.
+pub fn brute_force(ciphertext: &str, namespace: Option<&str>, top: usize) -> Result<Vec<Candidate>> {
+	let chars: Vec<char> = namespace.map_or(DEFAULT_NAMESPACE.to_vec(), |ns| ns.chars().collect());
+	let set: HashSet<_> = chars.iter().copied().collect();
+	let ciphertext: Vec<_> = CaseFolding::default()
+		.apply(ciphertext, &set)
+		.chars()
+		.filter(|c| !c.is_whitespace())
+		.map(|c| chars.iter().position(|&n| n == c).ok_or_else(|| Error::UnknownChar(c, suggest(c, &chars))))
+		.collect::<Result<_>>()?;
+	if ciphertext.is_empty() || ciphertext.len() % 2 != 0 {
+		return Err("the ciphertext must have an even number of characters to try 2x2 keys".into());
+	}
+
+	let bigrams = bigram_table(&chars);
+	let decipher = |numbers: &[usize]| -> Vec<usize> {
+		// a key whose matrix is the inverse one ciphers back into the plaintext
+		ciphertext
+			.chunks(2)
+			.flat_map(|block| {
+				let v = |col: usize| (numbers[col] * block[0] + numbers[2 + col] * block[1]) % chars.len();
+				[v(0), v(1)]
+			})
+			.collect()
+	};
+	let score = |plaintext: &[usize]| -> f64 {
+		plaintext.windows(2).map(|pair| bigrams[pair[0]][pair[1]]).sum()
+	};
+
+	let mut ranked: Vec<_> = keyspace::enumerate(2, namespace)?
+		.map(|entry| (score(&decipher(&entry.numbers)), entry.numbers))
+		.collect();
+	debug!(keys = ranked.len(), "every invertible key was tried");
+	ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+	Ok(ranked
+		.into_iter()
+		.take(top)
+		.map(|(score, numbers)| {
+			let key = ModMatrix::new(2, 2, numbers.clone(), chars.len())
+				.inverse()
+				.expect("the enumerated keys are invertible")
+				.into_vec();
+			Candidate {
+				key: key.into_iter().map(|i| chars[i]).collect(),
+				score,
+				plaintext: decipher(&numbers).into_iter().map(|i| chars[i]).collect(),
+			}
+		})
+		.collect())
+}
+
+/// Learns the log probability of every character of the `namespace` being
+/// followed by every other one in english text, indexed by their positions.
+/// Every pair is counted once more, so none is impossible.
+fn bigram_table(namespace: &[char]) -> Vec<Vec<f64>> {
+	let corpus: Vec<_> = CORPUS
+		.to_uppercase()
+		.chars()
+		.filter_map(|c| namespace.iter().position(|&n| n == c))
+		.collect();
+
+	let mut counts = vec![vec![1.0; namespace.len()]; namespace.len()];
+	for pair in corpus.windows(2) {
+		counts[pair[0]][pair[1]] += 1.0;
+	}
+
+	counts
+		.into_iter()
+		.map(|row| {
+			let total: f64 = row.iter().sum();
+			row.into_iter().map(|count| (count / total).ln()).collect()
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn two_by_two_keys_are_found_by_brute_force() {
+		let candidates = brute_force("CVWAUOUWMNCQQDNBXGPBUMJEQHPWZKXWTWQWUHCVPTBSDQIIEERZBUCVFJZNSWWP", None, 3).unwrap();
+		assert_eq!(candidates.len(), 3);
+		assert_eq!(candidates[0].key, "HILL");
+		assert!(candidates[0].plaintext.starts_with("THEQUICKBROWNFOX"));
+		assert!(brute_force("ABC", None, 3).is_err());
+	}
+}
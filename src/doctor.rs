@@ -0,0 +1,127 @@
+use std::env;
+use std::io::{self, IsTerminal as _};
+
+use colored::Colorize as _;
+
+use crate::config::{self, Config};
+use crate::i18n::Lang;
+
+/// Outcome of a single diagnostic check.
+enum Status {
+	Ok,
+	/// Something may not work as expected, along with how to address it
+	Warn(String),
+	/// The checked facility is not part of this build
+	Skipped,
+}
+
+/// Checks the environment the program runs in, printing a line per check and
+/// an actionable hint for every one that may cause trouble.
+pub fn run() {
+	let checks = [
+		("Terminal", terminal()),
+		("Color support", color()),
+		("Locale", locale()),
+		("Syslog audit log", syslog()),
+		("Config file", config()),
+		("Keystore", Status::Skipped),
+		("Clipboard", Status::Skipped),
+	];
+
+	let mut warnings = 0;
+	for (name, status) in checks {
+		match status {
+			Status::Ok => eprintln!("  {} {name}", "ok".green().bold()),
+			Status::Warn(hint) => {
+				warnings += 1;
+				eprintln!("  {} {name}: {hint}", "warn".yellow().bold());
+			},
+			Status::Skipped => {
+				eprintln!("  {} {name}: not supported by this build", "skip".dimmed());
+			},
+		}
+	}
+
+	match warnings {
+		0 => eprintln!("{}", "No problems found.".green()),
+		n => eprintln!("{}", format!("{n} possible problem(s) found.").yellow()),
+	}
+}
+
+/// Checks that the interactive modes, like the wizard and the tui, can run.
+fn terminal() -> Status {
+	if io::stdin().is_terminal() && io::stderr().is_terminal() {
+		Status::Ok
+	} else {
+		Status::Warn(
+			"stdin or stderr is not a terminal, so the wizard and the tui are not available".into()
+		)
+	}
+}
+
+/// Checks whether the output can be styled.
+fn color() -> Status {
+	if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+		return Status::Warn("NO_COLOR is set, pass --color always to style the output anyway".into());
+	}
+	match env::var("TERM") {
+		Ok(term) if term == "dumb" => {
+			Status::Warn("TERM is `dumb`, so styled output may be garbled; pass --plain".into())
+		},
+		Err(_) if cfg!(unix) => {
+			Status::Warn("TERM is not set, so styled output may not be supported".into())
+		},
+		_ => Status::Ok,
+	}
+}
+
+/// Checks that the locale names a language the messages are available in.
+fn locale() -> Status {
+	let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+		.iter()
+		.filter_map(|var| env::var(var).ok())
+		.find(|locale| !locale.is_empty());
+
+	let Some(locale) = locale else {
+		return Status::Warn("no locale is set, so messages are shown in english; pass --lang".into());
+	};
+
+	// the `C` and `POSIX` locales stand for no language at all
+	let language = locale.split(['_', '.', '-', '@']).next().unwrap_or_default();
+	if Lang::VARIANTS.contains(&language) || ["C", "POSIX"].contains(&language) {
+		Status::Ok
+	} else {
+		Status::Warn(format!(
+			"the locale `{locale}` has no translation, so messages are shown in english; \
+			pass --lang with one of: {}", Lang::VARIANTS.join(", ")
+		))
+	}
+}
+
+/// Checks that the local syslog daemon can receive audit records.
+fn syslog() -> Status {
+	if !cfg!(unix) {
+		return Status::Skipped;
+	}
+	if std::path::Path::new("/dev/log").exists() {
+		Status::Ok
+	} else {
+		Status::Warn("/dev/log does not exist, so --audit-log syslog will fail; log to a file instead".into())
+	}
+}
+
+/// Checks that the default config file, if any, can be read and parsed.
+fn config() -> Status {
+	match config::default_path().filter(|path| path.exists()) {
+		Some(path) => match Config::load(&path) {
+			Ok(_) => Status::Ok,
+			Err(e) => {
+				// the parse errors go on with the offending lines, left out here
+				let e = e.to_string();
+				let reason = e.lines().next().unwrap_or_default();
+				Status::Warn(format!("{reason}, so every cipher and decipher fails; fix or remove it"))
+			},
+		},
+		None => Status::Ok,
+	}
+}
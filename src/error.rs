@@ -1,3 +1,5 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// A specified [`Result`] type for the `cipher` and `decipher` operations.
@@ -5,10 +7,66 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 /// The error type for `cipher` and `decipher` operations.
 #[derive(Debug, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[non_exhaustive]
 pub enum Error {
 	#[error("{0}")]
 	ProcessingError(String),
+
+	#[error("the supplied key must be square in length")]
+	NonSquareKey,
+
+	#[error("the supplied key is empty")]
+	EmptyKey,
+
+	#[error("the source text must be filled, but no fill letter was supplied")]
+	MissingFillLetter,
+
+	#[error("the source text has {len} characters, which do not split into blocks of {dimension}")]
+	PartialBlock { len: usize, dimension: usize },
+
+	#[error("the specified key cannot be used. [matrix's det 0 or has factors with {0}]")]
+	InvalidKeyMatrix(usize),
+
+	#[error("the supplied namespace has duplicated characters")]
+	DuplicatedNamespaceChars,
+
+	#[error("the supplied namespace must have at least 2 characters")]
+	NamespaceTooShort,
+
+	#[error("the character '{0}' is not present in the namespace{}", hint(*.0, .1))]
+	UnknownChar(char, Option<Suggestion>),
+
+	#[error("the key dimension {dimension} is over the limit of {max} given by --max-key-dimension")]
+	KeyTooLarge { dimension: usize, max: usize },
+
+	#[error("the operation was interrupted, leaving only its complete outputs behind")]
+	Interrupted,
+}
+
+/// Likely fix of a character missing from the namespace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Suggestion {
+	/// The character is in the namespace in another case
+	Case(char),
+	/// The character is in the namespace without its accents
+	Unaccented(char),
+	/// The character is a combining mark, which a normalization composes with
+	/// the character before it
+	Normalize,
+}
+
+/// Words the given `suggestion` for the missing character `c`, if any.
+fn hint(c: char, suggestion: &Option<Suggestion>) -> String {
+	match suggestion {
+		Some(Suggestion::Case(other)) => format!("; did you mean '{other}'?"),
+		Some(Suggestion::Unaccented(other)) => {
+			format!("; did you mean '{other}', or to add '{c}' to the namespace?")
+		},
+		Some(Suggestion::Normalize) => "; did you mean to enable --normalize?".to_owned(),
+		None => String::new(),
+	}
 }
 
 impl From<&'static str> for Error {
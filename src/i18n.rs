@@ -0,0 +1,247 @@
+use std::env;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::error::{Error, Suggestion};
+
+/// Language the program's messages are shown in, once it is set.
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// Languages the program's messages are available in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lang {
+	En,
+	Es,
+}
+
+impl Lang {
+	pub const VARIANTS: [&'static str; 2] = ["en", "es"];
+
+	/// Detects the language from the locale environment variables, falling
+	/// back to english if the locale is unknown or not supported.
+	pub fn detect() -> Lang {
+		["LC_ALL", "LC_MESSAGES", "LANG"]
+			.iter()
+			.filter_map(|var| env::var(var).ok())
+			.find(|locale| !locale.is_empty())
+			.map_or(Lang::En, |locale| {
+				match locale.split(['_', '.', '-']).next() {
+					Some("es") => Lang::Es,
+					_ => Lang::En,
+				}
+			})
+	}
+}
+
+impl FromStr for Lang {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"en" => Ok(Lang::En),
+			"es" => Ok(Lang::Es),
+			_ => Err(format!("unsupported language '{s}'")),
+		}
+	}
+}
+
+/// Messages shown to the user that are available in every [`Lang`].
+#[derive(Debug, Clone, Copy)]
+pub enum Msg {
+	ReportResult,
+	UsedKey,
+	EffectiveKey,
+	SourceText,
+	ResultText,
+	WrittenTo,
+	Filled,
+	Namespace,
+	DefaultNamespace,
+	GeneratedNamespace,
+	KeyDimension,
+	BlockCount,
+	Version,
+	StartedAt,
+	FinishedAt,
+	Diff,
+	Padding,
+	Dump,
+	Annotation,
+	BlockTable,
+	SourceVector,
+	ResultVector,
+	Block,
+	Text,
+	Indices,
+	Product,
+	Reduced,
+	Comparison,
+	FirstDifference,
+	Position,
+	Identical,
+	Match,
+	ProcessedFiles,
+	ProcessedRows,
+	FailedBlocks,
+	Explanation,
+	Summary,
+	Processed,
+	Failed,
+	FilledCount,
+	Error,
+}
+
+/// Sets the language of the program's messages. Only the first call has
+/// effect.
+pub fn set_lang(lang: Lang) {
+	LANG.set(lang).ok();
+}
+
+/// Retrieves the language of the program's messages, english by default.
+pub fn lang() -> Lang {
+	LANG.get().copied().unwrap_or(Lang::En)
+}
+
+/// Translates the given message into the current language.
+pub fn tr(msg: Msg) -> &'static str {
+	match (lang(), msg) {
+		(Lang::En, Msg::ReportResult) => "Report result",
+		(Lang::En, Msg::UsedKey) => "Used key",
+		(Lang::En, Msg::EffectiveKey) => "Effective key",
+		(Lang::En, Msg::SourceText) => "Source text",
+		(Lang::En, Msg::ResultText) => "Result text",
+		(Lang::En, Msg::WrittenTo) => "Written to",
+		(Lang::En, Msg::Filled) => "Filled?",
+		(Lang::En, Msg::Namespace) => "Namespace",
+		(Lang::En, Msg::DefaultNamespace) => "Default namespace",
+		(Lang::En, Msg::GeneratedNamespace) => "Generated namespace, to decipher with -n",
+		(Lang::En, Msg::KeyDimension) => "Key dimension",
+		(Lang::En, Msg::BlockCount) => "Blocks",
+		(Lang::En, Msg::Version) => "Version",
+		(Lang::En, Msg::StartedAt) => "Started at",
+		(Lang::En, Msg::FinishedAt) => "Finished at",
+		(Lang::En, Msg::Diff) => "Diff",
+		(Lang::En, Msg::Padding) => "Padding",
+		(Lang::En, Msg::Dump) => "Dump",
+		(Lang::En, Msg::Annotation) => "Annotation",
+		(Lang::En, Msg::BlockTable) => "Blocks table",
+		(Lang::En, Msg::SourceVector) => "Source vector",
+		(Lang::En, Msg::ResultVector) => "Result vector",
+		(Lang::En, Msg::Block) => "Block",
+		(Lang::En, Msg::Text) => "Text",
+		(Lang::En, Msg::Indices) => "Indices",
+		(Lang::En, Msg::Product) => "Product",
+		(Lang::En, Msg::Reduced) => "Reduced",
+		(Lang::En, Msg::Comparison) => "Comparison",
+		(Lang::En, Msg::FirstDifference) => "First difference",
+		(Lang::En, Msg::Position) => "position",
+		(Lang::En, Msg::Identical) => "none, the texts are identical",
+		(Lang::En, Msg::Match) => "Match",
+		(Lang::En, Msg::ProcessedFiles) => "Processed files",
+		(Lang::En, Msg::ProcessedRows) => "Processed rows",
+		(Lang::En, Msg::FailedBlocks) => "Failed blocks",
+		(Lang::En, Msg::Explanation) => "Explanation",
+		(Lang::En, Msg::Summary) => "Summary",
+		(Lang::En, Msg::Processed) => "processed",
+		(Lang::En, Msg::Failed) => "failed",
+		(Lang::En, Msg::FilledCount) => "filled",
+		(Lang::En, Msg::Error) => "Error",
+		(Lang::Es, Msg::ReportResult) => "Resultado",
+		(Lang::Es, Msg::UsedKey) => "Clave usada",
+		(Lang::Es, Msg::EffectiveKey) => "Clave efectiva",
+		(Lang::Es, Msg::SourceText) => "Texto fuente",
+		(Lang::Es, Msg::ResultText) => "Texto resultante",
+		(Lang::Es, Msg::WrittenTo) => "Escrito en",
+		(Lang::Es, Msg::Filled) => "¿Rellenado?",
+		(Lang::Es, Msg::Namespace) => "Alfabeto",
+		(Lang::Es, Msg::DefaultNamespace) => "Alfabeto por defecto",
+		(Lang::Es, Msg::GeneratedNamespace) => "Alfabeto generado, para descifrar con -n",
+		(Lang::Es, Msg::KeyDimension) => "Dimensión de la clave",
+		(Lang::Es, Msg::BlockCount) => "Bloques",
+		(Lang::Es, Msg::Version) => "Versión",
+		(Lang::Es, Msg::StartedAt) => "Iniciado",
+		(Lang::Es, Msg::FinishedAt) => "Finalizado",
+		(Lang::Es, Msg::Diff) => "Diferencias",
+		(Lang::Es, Msg::Padding) => "Relleno",
+		(Lang::Es, Msg::Dump) => "Volcado",
+		(Lang::Es, Msg::Annotation) => "Anotación",
+		(Lang::Es, Msg::BlockTable) => "Tabla de bloques",
+		(Lang::Es, Msg::SourceVector) => "Vector fuente",
+		(Lang::Es, Msg::ResultVector) => "Vector resultante",
+		(Lang::Es, Msg::Block) => "Bloque",
+		(Lang::Es, Msg::Text) => "Texto",
+		(Lang::Es, Msg::Indices) => "Índices",
+		(Lang::Es, Msg::Product) => "Producto",
+		(Lang::Es, Msg::Reduced) => "Reducido",
+		(Lang::Es, Msg::Comparison) => "Comparación",
+		(Lang::Es, Msg::FirstDifference) => "Primera diferencia",
+		(Lang::Es, Msg::Position) => "posición",
+		(Lang::Es, Msg::Identical) => "ninguna, los textos son idénticos",
+		(Lang::Es, Msg::Match) => "Coincidencia",
+		(Lang::Es, Msg::ProcessedFiles) => "Archivos procesados",
+		(Lang::Es, Msg::ProcessedRows) => "Filas procesadas",
+		(Lang::Es, Msg::FailedBlocks) => "Bloques fallidos",
+		(Lang::Es, Msg::Explanation) => "Explicación",
+		(Lang::Es, Msg::Summary) => "Resumen",
+		(Lang::Es, Msg::Processed) => "procesados",
+		(Lang::Es, Msg::Failed) => "fallidos",
+		(Lang::Es, Msg::FilledCount) => "rellenados",
+		(Lang::Es, Msg::Error) => "Error",
+	}
+}
+
+/// Translates a yes/no answer into the current language.
+pub fn tr_bool(value: bool) -> &'static str {
+	match (lang(), value) {
+		(Lang::En, value) => if value { "true" } else { "false" },
+		(Lang::Es, value) => if value { "sí" } else { "no" },
+	}
+}
+
+/// Translates the given error's message into the current language. Errors
+/// without a known translation keep their original message.
+pub fn tr_error(err: &Error) -> String {
+	if lang() == Lang::En {
+		return err.to_string();
+	}
+
+	match err {
+		Error::NonSquareKey => {
+			"la clave suministrada debe tener una longitud cuadrada".to_owned()
+		},
+		Error::EmptyKey => "la clave suministrada está vacía".to_owned(),
+		Error::MissingFillLetter => {
+			"el texto fuente debe rellenarse, pero no se suministró una letra de relleno".to_owned()
+		},
+		Error::PartialBlock { len, dimension } => format!(
+			"el texto fuente tiene {len} caracteres, que no se dividen en bloques de {dimension}"
+		),
+		Error::InvalidKeyMatrix(n) => format!(
+			"la clave especificada no puede usarse. [el determinante de la matriz es 0 o tiene factores con {n}]"
+		),
+		Error::DuplicatedNamespaceChars => {
+			"el alfabeto suministrado tiene caracteres duplicados".to_owned()
+		},
+		Error::NamespaceTooShort => {
+			"el alfabeto suministrado debe tener al menos 2 caracteres".to_owned()
+		},
+		Error::UnknownChar(c, suggestion) => {
+			let hint = match suggestion {
+				Some(Suggestion::Case(other)) => format!("; ¿quiso decir '{other}'?"),
+				Some(Suggestion::Unaccented(other)) => {
+					format!("; ¿quiso decir '{other}', o añadir '{c}' al alfabeto?")
+				},
+				Some(Suggestion::Normalize) => "; ¿quiso activar --normalize?".to_owned(),
+				None => String::new(),
+			};
+			format!("el carácter '{c}' no está presente en el alfabeto{hint}")
+		},
+		Error::KeyTooLarge { dimension, max } => format!(
+			"la dimensión de la clave {dimension} supera el límite de {max} dado por --max-key-dimension"
+		),
+		Error::Interrupted => {
+			"la operación fue interrumpida, dejando atrás solo sus salidas completas".to_owned()
+		},
+		_ => err.to_string(),
+	}
+}
@@ -1,5 +1,16 @@
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use encoding_rs::Encoding;
 use structopt::StructOpt;
-use structopt::clap::AppSettings;
+use structopt::clap::{AppSettings, Shell};
+
+use crate::audit::AuditSink;
+use crate::i18n::Lang;
+use crate::key::KeyForm;
+use crate::parts::Part;
+use crate::process::{BadBlocks, CaseFolding, Normalization};
 
 /// Cipher and decipher text using the Hill's cipher method
 #[derive(Debug, StructOpt)]
@@ -11,8 +22,74 @@ use structopt::clap::AppSettings;
 	setting = AppSettings::ColoredHelp,
 )]
 pub struct Args {
+	/// When to style the output: auto, always or never
+	#[structopt(
+		long,
+		global = true,
+		default_value = "auto",
+		possible_values = &ColorChoice::VARIANTS
+	)]
+	pub color: ColorChoice,
+
+	/// Print plain `label: value` lines without any styling or decoration
+	#[structopt(long, global = true)]
+	pub plain: bool,
+
+	/// Explain the errors, telling what was computed and what would be valid
+	/// instead. Enabled by --verbose too
+	#[structopt(long, global = true)]
+	pub explain_errors: bool,
+
+	/// Record the time spent in every stage of the process as folded stack
+	/// samples into a file, to be rendered as a flamegraph. Only available if
+	/// built with the `profiling` feature
+	#[structopt(long, global = true, parse(from_os_str))]
+	pub profile: Option<PathBuf>,
+
+	/// Print debug logs of the processes (-vv for trace logs)
+	#[structopt(short, long, global = true, parse(from_occurrences))]
+	pub verbose: u8,
+
+	/// Hide the progress bars of the large files and batches
+	#[structopt(short, long, global = true)]
+	pub quiet: bool,
+
+	/// Append an audit record of the operation to a JSON lines file, or send
+	/// it to the local syslog daemon if `syslog` is given
+	#[structopt(long, global = true)]
+	pub audit_log: Option<AuditSink>,
+
+	/// Language of the messages, detected from the locale if not given
+	#[structopt(long, global = true, possible_values = &Lang::VARIANTS)]
+	pub lang: Option<Lang>,
+
+	/// Seed of the random generators, to get reproducible outputs
+	#[structopt(long, global = true)]
+	pub seed: Option<u64>,
+
+	/// Largest source text loaded as a whole, in bytes, with an optional K, M
+	/// or G suffix; larger plain source files are streamed
+	#[structopt(long, global = true, default_value = "64M")]
+	pub max_size: ByteSize,
+
+	/// Largest key dimension accepted, any by default
+	#[structopt(long, global = true)]
+	pub max_key_dimension: Option<usize>,
+
+	/// Largest number of characters of a custom namespace
+	#[structopt(long, global = true, default_value = "4096")]
+	pub max_namespace: usize,
+
+	/// File remembering where the key came from, but never the key itself,
+	/// and the fill letter, namespace and format of every cipher or decipher,
+	/// so that the next one can reuse them with --last
+	#[structopt(long, global = true, parse(from_os_str))]
+	pub session: Option<PathBuf>,
+
+	/// Operation to perform. If none is given on a terminal, an interactive
+	/// wizard asks for the needed information
 	#[structopt(subcommand)]
-	pub cmd: Command,
+	pub cmd: Option<Command>,
 }
 
 // This struct represents the application's available commands
@@ -21,39 +98,887 @@ pub struct Args {
 pub enum Command {
 	/// Cipher a given source text
 	Cipher {
-		/// Key to cipher the source text
+		#[structopt(flatten)]
+		key: KeyOpts,
+
+		#[structopt(flatten)]
+		source: SourceOpts,
+
+		/// Source text's fill letter
+		#[structopt(short, long, required_unless_one = &["last", "config"], parse(try_from_str = unescape_char))]
+		fill_letter: Option<char>,
+
+		/// Custom namespace, or name of a preset, for the base of the
+		/// algorithm, or `auto` to build it out of the characters of the
+		/// source text, key and fill letter, and `auto+<namespace>` to add the
+		/// ones of another namespace or preset
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
+
+		/// Number of times the key is applied, reporting the single key
+		/// equivalent to all of them
+		#[structopt(long, default_value = "1")]
+		iterations: NonZeroUsize,
+
+		#[structopt(flatten)]
+		output: OutputOpts,
+	},
+
+	/// Decipher a given source text
+	Decipher {
+		#[structopt(flatten)]
+		key: KeyOpts,
+
+		#[structopt(flatten)]
+		source: SourceOpts,
+
+		/// Known source text's fill letter
+		#[structopt(short, long, parse(try_from_str = unescape_char))]
+		fill_letter: Option<char>,
+
+		/// Known namespace, or name of a preset, used to decipher source text
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
+
+		/// Number of times the key was applied to cipher the source text
+		#[structopt(long, default_value = "1")]
+		iterations: NonZeroUsize,
+
+		#[structopt(flatten)]
+		output: OutputOpts,
+	},
+
+	/// Decipher a message split into parts, checking that none is missing
+	Join {
+		#[structopt(flatten)]
+		key: KeyOpts,
+
+		/// Files holding the parts of the message, in any order
+		#[structopt(parse(from_os_str), required = true)]
+		parts: Vec<PathBuf>,
+
+		/// Known source text's fill letter
+		#[structopt(short, long, parse(try_from_str = unescape_char))]
+		fill_letter: Option<char>,
+
+		/// Known namespace, or name of a preset, used to decipher source text
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
+
+		#[structopt(flatten)]
+		output: OutputOpts,
+	},
+
+	/// Cipher or decipher a column of a CSV or TSV table, writing the table
+	/// with the results in a new column
+	Batch {
+		#[structopt(flatten)]
+		key: KeyOpts,
+
+		/// Table to read the rows from
+		#[structopt(parse(from_os_str))]
+		input: PathBuf,
+
+		/// Name of the column holding the texts to process
 		#[structopt(short, long)]
-		key: String,
+		column: String,
+
+		/// Name of the column the results are written to
+		#[structopt(long, default_value = "result")]
+		result_column: String,
 
-		/// Source text to cipher
+		/// Decipher the texts instead of ciphering them
 		#[structopt(short, long)]
-		source: String,
+		decipher: bool,
 
-		/// Source text's fill letter
+		/// Fill letter of the texts
+		#[structopt(short, long, parse(try_from_str = unescape_char))]
+		fill_letter: Option<char>,
+
+		/// Custom namespace, or name of a preset, for the base of the algorithm
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
+
+		/// Field delimiter of the table, a tab for .tsv files and a comma
+		/// otherwise by default
+		#[structopt(long, parse(try_from_str = unescape_char))]
+		delimiter: Option<char>,
+
+		/// File to write the table to, instead of stdout
+		#[structopt(short, long, parse(from_os_str))]
+		output: Option<PathBuf>,
+	},
+
+	/// Start an interactive session that keeps the settings loaded
+	Repl {
+		/// Key to load at the start of the session
+		#[structopt(short, long)]
+		key: Option<String>,
+
+		/// Fill letter to load at the start of the session
+		#[structopt(short, long, parse(try_from_str = unescape_char))]
+		fill_letter: Option<char>,
+
+		/// Namespace to load at the start of the session
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
+	},
+
+	/// Compare two texts, reporting where they first differ and how much they
+	/// match
+	Compare {
+		/// Text that was expected, e.g. a known ciphertext
+		expected: String,
+
+		/// Text that was produced
+		actual: String,
+
+		/// Size of the blocks the texts are split into, usually the key's
+		/// dimension, to report the block of the first difference
 		#[structopt(short, long)]
-		fill_letter: char,
+		block_size: Option<usize>,
+	},
 
-		/// Custom namespace for the base of the algorithm
+	/// Generate a random sample text, for benchmarks or exercises
+	RandText {
+		/// Number of characters of the text
 		#[structopt(short, long)]
+		length: usize,
+
+		/// Namespace, or name of a preset, the characters are picked from
+		#[structopt(short, long, parse(try_from_str = unescape))]
 		namespace: Option<String>,
+
+		/// Sample pseudo-English text instead of uniformly random characters
+		#[structopt(long)]
+		english: bool,
 	},
 
-	/// Decipher a given source text
-	Decipher {
-		/// Key to decipher the source text
+	/// Print the frequencies of the characters and n-grams of a ciphertext,
+	/// along with its index of coincidence
+	Analyze {
+		/// Ciphertext to analyze
 		#[structopt(short, long)]
-		key: String,
+		source: String,
+
+		/// Namespace, or name of a preset, whose characters are analyzed
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
 
-		/// Cipher source text
+		/// Length of the counted n-grams
+		#[structopt(long, default_value = "2")]
+		ngram: usize,
+
+		/// Number of most frequent characters and n-grams printed
+		#[structopt(long, default_value = "10")]
+		top: usize,
+
+		/// Format the analysis is printed in
+		#[structopt(long, default_value = "text", possible_values = &["text", "json"])]
+		format: Format,
+	},
+
+	/// Search the key of a ciphertext, ranking the texts it deciphers into by
+	/// how much they look like english
+	Crack {
+		/// Ciphertext whose key is searched
 		#[structopt(short, long)]
 		source: String,
 
-		/// Known source text's fill letter
+		/// Known namespace used to cipher the source text
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
+
+		/// Try every invertible 2x2 key, the only search available so far
+		#[structopt(long)]
+		brute_force: bool,
+
+		/// Number of best ranked keys printed
+		#[structopt(long, default_value = "5")]
+		top: usize,
+	},
+
+	/// Work with keys without processing any text
+	Key {
+		#[structopt(subcommand)]
+		cmd: KeyCommand,
+	},
+
+	/// Explore the keys available for a namespace
+	Keyspace {
+		#[structopt(subcommand)]
+		cmd: KeyspaceCommand,
+	},
+
+	/// Print the capabilities and limits of this binary
+	Info,
+
+	/// Check the environment for common problems
+	Doctor,
+
+	/// Open a full-screen interface previewing the result while typing
+	Tui,
+
+	/// Print the completion script for the given shell
+	Completions {
+		/// Shell to generate the completion script for
+		#[structopt(possible_values = &Shell::variants())]
+		shell: Shell,
+	},
+
+	/// Print the values that can be completed for the given kind
+	#[structopt(setting = AppSettings::Hidden)]
+	Complete {
+		/// Kind of the values to complete
+		#[structopt(possible_values = &CompletionKind::VARIANTS)]
+		kind: CompletionKind,
+	},
+}
+
+/// Kinds of values that the completion scripts complete dynamically.
+#[derive(Debug, Clone, Copy)]
+pub enum CompletionKind {
+	Namespaces,
+}
+
+impl KeyOpts {
+	/// Whether any of the sources of the key was given.
+	pub fn is_given(&self) -> bool {
+		self.key.is_some() || self.key_qr.is_some() || self.key_env.is_some() || self.key_file.is_some()
+	}
+}
+
+impl CompletionKind {
+	pub const VARIANTS: [&'static str; 1] = ["namespaces"];
+}
+
+impl FromStr for CompletionKind {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"namespaces" => Ok(CompletionKind::Namespaces),
+			_ => Err(format!("unknown completion kind '{s}'")),
+		}
+	}
+}
+
+// This enum represents the commands working with keys
+#[derive(Debug, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum KeyCommand {
+	/// Convert a key between its letters, its numeric matrix like
+	/// `5,9;2,17` and its JSON representation
+	Convert {
+		/// Key to convert, in the form given by --from
 		#[structopt(short, long)]
-		fill_letter: Option<char>,
+		key: String,
+
+		/// Form the key is written in
+		#[structopt(long, possible_values = &KeyForm::VARIANTS)]
+		from: KeyForm,
+
+		/// Namespace, or name of a preset, relating the key's letters and
+		/// numbers
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
+
+		/// Form to convert the key to
+		#[structopt(long, possible_values = &KeyForm::VARIANTS)]
+		to: KeyForm,
+	},
+
+	/// Generate a random invertible key, the same one for the same --seed,
+	/// dimension and namespace
+	Generate {
+		/// Dimension of the key's matrix
+		#[structopt(short, long, default_value = "3")]
+		dimension: usize,
+
+		/// Namespace, or name of a preset, the key's letters are picked from
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
 
-		/// Known namespace used to decipher source text
+		/// Form the key is written in
+		#[structopt(long, default_value = "letters", possible_values = &KeyForm::VARIANTS)]
+		to: KeyForm,
+	},
+}
+
+// This enum represents the commands exploring a keyspace
+#[derive(Debug, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum KeyspaceCommand {
+	/// List the invertible keys of a dimension, with their numbers and
+	/// determinant
+	Enumerate {
+		/// Dimension of the keys' matrices
+		#[structopt(short, long, default_value = "2")]
+		dimension: usize,
+
+		/// Namespace, or name of a preset, of the keys
+		#[structopt(short, long, parse(try_from_str = unescape))]
+		namespace: Option<String>,
+
+		/// Largest number of keys listed
 		#[structopt(short, long)]
-		namespace: Option<String>
+		limit: Option<usize>,
+
+		/// List only the keys whose determinant, modulo the namespace length,
+		/// is the given one
+		#[structopt(long)]
+		determinant: Option<usize>,
+
+		/// Format the keys are listed in
+		#[structopt(long, default_value = "text", possible_values = &["text", "csv", "tsv"])]
+		format: Format,
+	},
+}
+
+// Options shared by the `cipher` and `decipher` commands about where the key
+// is taken from
+#[derive(Debug, Default, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct KeyOpts {
+	/// Key to cipher or decipher the source text, or `pass:<entry>` to take
+	/// it from an entry of the password store. Taken from the HILL_CIPHER_KEY
+	/// environment variable if no other key is given
+	#[structopt(short, long)]
+	pub key: Option<String>,
+
+	/// Image holding a QR code whose payload is the key
+	#[structopt(long, parse(from_os_str), conflicts_with = "key")]
+	pub key_qr: Option<PathBuf>,
+
+	/// Environment variable to take the key from, so it is kept out of the
+	/// shell history. The report redacts it
+	#[structopt(long, conflicts_with_all = &["key", "key-qr"])]
+	pub key_env: Option<String>,
+
+	/// File holding the key, which should be readable by its owner only
+	#[structopt(long, parse(from_os_str), conflicts_with_all = &["key", "key-qr", "key-env"])]
+	pub key_file: Option<PathBuf>,
+
+	/// Reuse the settings of the last cipher or decipher remembered in the
+	/// --session file for the ones not given
+	#[structopt(long)]
+	pub last: bool,
+
+	/// TOML file holding the default key, fill-letter and namespace, which
+	/// the given ones override. By default, the `config.toml` file in the
+	/// configuration directory, if it exists
+	#[structopt(long, parse(from_os_str))]
+	pub config: Option<PathBuf>,
+}
+
+// Options shared by the `cipher` and `decipher` commands about where the
+// source text is taken from
+#[derive(Debug, Default, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct SourceOpts {
+	/// Source text to cipher or decipher, or `-` to read it from stdin. Given
+	/// many times, every text is processed with the same settings into a
+	/// single report listing them
+	#[structopt(
+		short,
+		long,
+		number_of_values = 1,
+		required_unless_one = &["source-file", "source-url", "archive"]
+	)]
+	pub source: Vec<String>,
+
+	/// File to read the source text from, instead of --source
+	#[structopt(long, parse(from_os_str), conflicts_with = "source")]
+	pub source_file: Option<PathBuf>,
+
+	/// HTTP(S) URL whose body is fetched as the source text. Only available
+	/// if built with the `url` feature
+	#[structopt(long, conflicts_with_all = &["source", "source-file"])]
+	pub source_url: Option<String>,
+
+	/// Tar archive, optionally gzip compressed, whose files are processed one
+	/// by one into a new archive written to --output. A directory is
+	/// processed the same way into the directory given as --output
+	#[structopt(
+		long,
+		parse(from_os_str),
+		conflicts_with_all = &["source", "source-file", "source-url"],
+		requires = "output"
+	)]
+	pub archive: Option<PathBuf>,
+
+	/// Transform the names of the archive's entries too, over the `filename`
+	/// namespace preset, leaving any other character in place
+	#[structopt(long, requires = "archive")]
+	pub cipher_names: bool,
+
+	/// Copy the archive's entries matching the given glob as they are,
+	/// without processing them. Globs without a `/` are matched against
+	/// every name along the entries' paths
+	#[structopt(long, requires = "archive", number_of_values = 1)]
+	pub exclude: Vec<String>,
+
+	/// Process the archive's hidden entries too, the ones with a name
+	/// starting with a dot, which are copied as they are otherwise
+	#[structopt(long, requires = "archive")]
+	pub hidden: bool,
+
+	/// Replace the archive's symbolic links to files with the processed
+	/// contents of their targets, instead of copying them as they are
+	#[structopt(long, requires = "archive")]
+	pub follow_symlinks: bool,
+
+	/// Number of the archive's files, or of the source texts, processed at
+	/// once, and with the `rayon` feature of threads the blocks of large
+	/// texts are processed on, as many as the available CPUs by default
+	#[structopt(short, long)]
+	pub jobs: Option<usize>,
+
+	/// Encoding of the source file, URL or archive files: auto, or a label like utf-8, latin1,
+	/// utf-16le or utf-16be
+	#[structopt(long, default_value = "auto")]
+	pub encoding: EncodingChoice,
+
+	/// Process only the blocks of the source text from the given one on,
+	/// counted from 0. Line terminators do not count, unless processed as
+	/// namespace characters
+	#[structopt(long)]
+	pub offset: Option<usize>,
+
+	/// Process only the given number of blocks of the source text
+	#[structopt(long)]
+	pub length: Option<usize>,
+
+	/// Process every non-empty line of the source text as a text of its own,
+	/// into a single report listing them as many --source values are
+	#[structopt(long, conflicts_with_all = &["archive", "offset", "length"])]
+	pub lines: bool,
+
+	/// Process the source file block by block as it is read, with constant
+	/// memory, printing or writing only the result text. Pipes and other
+	/// streams are always processed so
+	#[structopt(long, requires = "source-file", conflicts_with_all = &["offset", "length", "lines", "in-place"])]
+	pub stream: bool,
+
+	/// Keep watching the source file, or every file right inside the source
+	/// directory, processing it into --output again whenever it changes,
+	/// until Ctrl-C. The output of a directory is a directory too
+	#[structopt(
+		long,
+		requires_all = &["source-file", "output"],
+		conflicts_with_all = &["offset", "length", "lines", "stream", "checkpoint"]
+	)]
+	pub watch: bool,
+
+	/// What is done with the blocks holding characters out of the namespace:
+	/// fail, skip them, or replace them with placeholder:<char>. The failed
+	/// blocks are reported
+	#[structopt(long, default_value = "fail")]
+	pub bad_blocks: BadBlocks,
+
+	/// Unicode normalization form the key, source text and namespace are put
+	/// in, so a character matches however it is composed: nfc, nfd or none
+	#[structopt(long, default_value = "nfc", possible_values = &Normalization::VARIANTS)]
+	pub normalize: Normalization,
+
+	/// How the characters out of the namespace are folded into it: upper to
+	/// take their uppercase, turkish to take the Turkish one of `i` and `ı`
+	/// too, or none to match them exactly
+	#[structopt(long, default_value = "upper", possible_values = &CaseFolding::VARIANTS)]
+	pub case_folding: CaseFolding,
+
+	/// Keep the byte order mark of the source file as part of the source text
+	#[structopt(long)]
+	pub keep_bom: bool,
+
+	/// Take as the source text the one hidden inside the given text with
+	/// --stego
+	#[structopt(long)]
+	pub extract_stego: bool,
+
+	/// Read the source text spelled with the NATO phonetic alphabet, as
+	/// written by `--format nato`
+	#[structopt(long)]
+	pub nato: bool,
+
+	/// How the line terminators of the source text are handled: preserve them
+	/// in the result, convert them to lf or crlf, or process them as any other
+	/// namespace character
+	#[structopt(long, default_value = "preserve", possible_values = &NewlineMode::VARIANTS)]
+	pub newlines: NewlineMode,
+
+	/// Replace the source file with the result text, keeping a copy of it
+	/// with a `.bak` extension
+	#[structopt(
+		long,
+		requires = "source-file",
+		conflicts_with_all = &["output", "offset", "length", "checkpoint", "stego"]
+	)]
+	pub in_place: bool,
+
+	/// Leave no backup of the source file replaced with --in-place
+	#[structopt(long, requires = "in-place")]
+	pub no_backup: bool,
+
+	/// Record the progress over the source file to the given file, resuming
+	/// from it if it exists. The output file is truncated to the recorded
+	/// progress and appended to
+	#[structopt(
+		long,
+		parse(from_os_str),
+		requires_all = &["source-file", "output"],
+		conflicts_with = "gzip-output"
+	)]
+	pub checkpoint: Option<PathBuf>,
+}
+
+// Options shared by the `cipher` and `decipher` commands about how their
+// results are presented
+#[derive(Debug, Default, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct OutputOpts {
+	/// Format of the report: text, json, yaml, toml, csv, tsv, or nato to
+	/// spell the result text with the NATO phonetic alphabet. Structured
+	/// formats are printed to stdout
+	#[structopt(long, default_value = "text", possible_values = &Format::VARIANTS)]
+	pub format: Format,
+
+	/// Show only the result text of the report
+	#[structopt(long)]
+	pub compact: bool,
+
+	/// Show the source and result texts aligned block by block, highlighting
+	/// the characters added by filling
+	#[structopt(long)]
+	pub diff: bool,
+
+	/// Show, block by block, the letters and indices of the source text, the
+	/// product vectors and the reduced indices of the result text
+	#[structopt(long)]
+	pub dump: bool,
+
+	/// Show the result text with the index of every character in the
+	/// namespace beneath it
+	#[structopt(long)]
+	pub annotate: bool,
+
+	/// Show a table with, block by block, the source text letters and
+	/// vector along with the result text vector and letters
+	#[structopt(long)]
+	pub block_table: bool,
+
+	/// Alphabet, or name of a preset, the ciphertext is written in, with as
+	/// many characters as the namespace: the result of ciphering is re-mapped
+	/// into it, and the source text of deciphering is re-mapped from it
+	#[structopt(long, parse(try_from_str = unescape))]
+	pub output_namespace: Option<String>,
+
+	/// Print only the result text to stdout, without the report, so it can be
+	/// piped into other commands. Many source texts are printed one per line
+	#[structopt(
+		long,
+		conflicts_with_all = &["format", "compact", "diff", "dump", "annotate", "block-table", "qr"]
+	)]
+	pub raw: bool,
+
+	/// Write the result text to the given file
+	#[structopt(short, long, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	/// Encoding of the output file, as a label like utf-8, latin1, utf-16le
+	/// or utf-16be
+	#[structopt(long, default_value = "utf-8")]
+	pub output_encoding: EncodingChoice,
+
+	/// Line terminators of the output file: preserve the ones of the result
+	/// text, or convert them to lf or crlf
+	#[structopt(long, default_value = "preserve", possible_values = &NewlineMode::VARIANTS[..3])]
+	pub output_newlines: NewlineMode,
+
+	/// Compress the output file with gzip
+	#[structopt(long, requires = "output")]
+	pub gzip_output: bool,
+
+	/// Write the whole report to the given file, as JSON, YAML or TOML after
+	/// its extension, or as plain `label: value` lines otherwise
+	#[structopt(long, parse(from_os_str))]
+	pub output_report: Option<PathBuf>,
+
+	/// Hide the result text inside the text of the given cover file, as zero
+	/// width characters that leave the cover reading the same
+	#[structopt(long, parse(from_os_str))]
+	pub stego: Option<PathBuf>,
+
+	/// Mark the result text as a part of a message, like 2/5, with a header
+	/// line that `join` checks
+	#[structopt(long)]
+	pub part: Option<Part>,
+
+	/// Render the result text as a QR code in the terminal
+	#[structopt(long)]
+	pub qr: bool,
+
+	/// Encode the used key in the QR code instead of the result text
+	#[structopt(long)]
+	pub qr_key: bool,
+
+	/// Save the QR code to a .png or .svg file
+	#[structopt(long, parse(from_os_str))]
+	pub qr_output: Option<PathBuf>,
+}
+
+/// Encoding the source file is decoded with.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum EncodingChoice {
+	/// Detected from the contents of the file
+	#[default]
+	Auto,
+	Label(&'static Encoding),
+}
+
+impl FromStr for EncodingChoice {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"auto" => Ok(EncodingChoice::Auto),
+			label => Encoding::for_label(label.as_bytes())
+				.map(EncodingChoice::Label)
+				.ok_or_else(|| format!("unknown encoding '{s}'")),
+		}
+	}
+}
+
+/// Resolves the escape sequences of the given argument, so that whitespace
+/// and other characters mangled by the shells can be given: `\t`, `\n`, `\r`,
+/// `\0`, `\s` for a space, `\\` for a backslash, `\xNN` for a character up to
+/// `\x7F` and `\u{N...}` for any character.
+pub fn unescape(s: &str) -> Result<String, String> {
+	let mut unescaped = String::with_capacity(s.len());
+	let mut chars = s.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			unescaped.push(c);
+			continue;
+		}
+
+		let escaped = match chars.next() {
+			Some('t') => '\t',
+			Some('n') => '\n',
+			Some('r') => '\r',
+			Some('0') => '\0',
+			Some('s') => ' ',
+			Some('\\') => '\\',
+			Some('x') => {
+				let code: String = chars.by_ref().take(2).collect();
+				u8::from_str_radix(&code, 16)
+					.ok()
+					.filter(|n| n.is_ascii() && code.len() == 2 && code.chars().all(|c| c.is_ascii_hexdigit()))
+					.map(char::from)
+					.ok_or_else(|| format!("invalid escape '\\x{code}', it must be \\x00 to \\x7F"))?
+			},
+			Some('u') => {
+				let rest = chars.as_str();
+				let code = rest
+					.strip_prefix('{')
+					.and_then(|rest| rest.split_once('}'))
+					.map(|(code, _)| code)
+					.ok_or("invalid escape '\\u', it must look like \\u{e9}")?;
+				chars = rest[code.len() + 2..].chars();
+				u32::from_str_radix(code, 16)
+					.ok()
+					.and_then(char::from_u32)
+					.ok_or_else(|| format!("invalid escape '\\u{{{code}}}', it is not a character"))?
+			},
+			Some(other) => return Err(format!("unknown escape '\\{other}'")),
+			None => return Err("the argument ends with a lone backslash".to_owned()),
+		};
+		unescaped.push(escaped);
+	}
+
+	Ok(unescaped)
+}
+
+/// Writes the given `text` with the escape sequences [`unescape`] resolves
+/// for its whitespace, control characters and backslashes, so it can be given
+/// back as an argument.
+pub fn escape(text: &str) -> String {
+	text.chars()
+		.map(|c| match c {
+			'\t' => "\\t".to_owned(),
+			'\n' => "\\n".to_owned(),
+			'\r' => "\\r".to_owned(),
+			'\0' => "\\0".to_owned(),
+			' ' => "\\s".to_owned(),
+			'\\' => "\\\\".to_owned(),
+			c if c.is_control() => format!("\\u{{{:x}}}", c as u32),
+			c => c.to_string(),
+		})
+		.collect()
+}
+
+/// Resolves the escape sequences of the given argument as [`unescape`] does,
+/// checking that a single character is left.
+pub fn unescape_char(s: &str) -> Result<char, String> {
+	let unescaped = unescape(s)?;
+	let mut chars = unescaped.chars();
+	match (chars.next(), chars.next()) {
+		(Some(c), None) => Ok(c),
+		_ => Err(format!("'{s}' is not a single character")),
+	}
+}
+
+/// A number of bytes, given with an optional K, M or G binary suffix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ByteSize(pub u64);
+
+impl FromStr for ByteSize {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (number, shift) = match s.char_indices().last() {
+			Some((i, 'K' | 'k')) => (&s[..i], 10),
+			Some((i, 'M' | 'm')) => (&s[..i], 20),
+			Some((i, 'G' | 'g')) => (&s[..i], 30),
+			_ => (s, 0),
+		};
+
+		number
+			.parse::<u64>()
+			.ok()
+			.and_then(|n| n.checked_mul(1 << shift))
+			.map(ByteSize)
+			.ok_or_else(|| format!("invalid size '{s}', it must look like 512K or 64M"))
+	}
+}
+
+/// How the line terminators of a source text are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum NewlineMode {
+	/// Kept out of the process and put back at the same place of the result
+	#[default]
+	Preserve,
+	/// Like `Preserve`, but converted to `\n`
+	Lf,
+	/// Like `Preserve`, but converted to `\r\n`
+	Crlf,
+	/// Processed as any other character, so they must be in the namespace
+	Namespace,
+}
+
+impl NewlineMode {
+	pub const VARIANTS: [&'static str; 4] = ["preserve", "lf", "crlf", "namespace"];
+}
+
+impl FromStr for NewlineMode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"preserve" => Ok(NewlineMode::Preserve),
+			"lf" => Ok(NewlineMode::Lf),
+			"crlf" => Ok(NewlineMode::Crlf),
+			"namespace" => Ok(NewlineMode::Namespace),
+			_ => Err(format!("unknown newline mode '{s}'")),
+		}
+	}
+}
+
+/// When the output of the program is styled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+	/// Styled only on terminals and if `NO_COLOR` is not set
+	Auto,
+	Always,
+	Never,
+}
+
+impl ColorChoice {
+	pub const VARIANTS: [&'static str; 3] = ["auto", "always", "never"];
+}
+
+impl FromStr for ColorChoice {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"auto" => Ok(ColorChoice::Auto),
+			"always" => Ok(ColorChoice::Always),
+			"never" => Ok(ColorChoice::Never),
+			_ => Err(format!("unknown color choice '{s}'")),
+		}
+	}
+}
+
+/// Formats the report of an operation can be printed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+	#[default]
+	Text,
+	Json,
+	Yaml,
+	Toml,
+	/// One comma separated row per processed item
+	Csv,
+	/// One tab separated row per processed item
+	Tsv,
+	/// Only the result text, spelled with the NATO phonetic alphabet
+	Nato,
+}
+
+impl Format {
+	pub const VARIANTS: [&'static str; 7] = ["text", "json", "yaml", "toml", "csv", "tsv", "nato"];
+
+	/// Retrieves the name the format is given by.
+	pub fn name(self) -> &'static str {
+		Self::VARIANTS[self as usize]
+	}
+}
+
+impl FromStr for Format {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(Format::Text),
+			"json" => Ok(Format::Json),
+			"yaml" => Ok(Format::Yaml),
+			"toml" => Ok(Format::Toml),
+			"csv" => Ok(Format::Csv),
+			"tsv" => Ok(Format::Tsv),
+			"nato" => Ok(Format::Nato),
+			_ => Err(format!("unknown format '{s}'")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keys_are_applied_at_least_once() {
+		let args = |iterations: &str| Args::from_iter_safe([
+			"hill_cypher", "cipher", "-k", "GYBNQKURP", "-f", "X", "-s", "ACT", "--iterations", iterations,
+		]);
+		assert!(args("0").is_err());
+		assert!(matches!(args("2").unwrap().cmd, Some(Command::Cipher { iterations, .. }) if iterations.get() == 2));
+	}
+
+	#[test]
+	fn joins_take_no_source_file_options() {
+		let args = |flag: &str| Args::from_iter_safe(["hill_cypher", "join", "-k", "GYBNQKURP", "a.txt", flag]);
+		assert!(args("-o=out.txt").is_ok());
+		assert!(args("--in-place").is_err());
+		assert!(args("--checkpoint=state.json").is_err());
+	}
+
+	#[test]
+	fn arguments_are_unescaped() {
+		assert_eq!(unescape(r"AB\s\t\n\x41\u{e9}\\").unwrap(), "AB \t\nA\u{e9}\\");
+		assert_eq!(unescape_char(r"\t").unwrap(), '\t');
+		assert!(unescape(r"\x4").is_err());
+		assert!(unescape(r"\u{110000}").is_err());
+		assert!(unescape(r"A\").is_err());
 	}
 }
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use signal_hook::consts::{SIGINT, SIGTERM};
+use signal_hook::flag;
+use tracing::debug;
+
+/// Status the program exits with when it is interrupted, as shells do for
+/// the processes stopped by a SIGINT.
+pub const EXIT_STATUS: i32 = 130;
+
+/// Whether an interruption was asked for, once the handlers are installed.
+static INTERRUPTED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+/// Installs the handlers of Ctrl-C and termination requests. The first one
+/// only records the request, so that the long runs stop at their next safe
+/// point, leaving their outputs and checkpoints complete; a second one
+/// terminates the program right away.
+pub fn install() {
+	let interrupted = INTERRUPTED.get_or_init(Arc::default);
+	for signal in [SIGINT, SIGTERM] {
+		// the shutdown is registered first, so it only fires once the flag is
+		// already set by an earlier request
+		let registered = flag::register_conditional_shutdown(signal, EXIT_STATUS, Arc::clone(interrupted))
+			.and_then(|_| flag::register(signal, Arc::clone(interrupted)));
+		if let Err(e) = registered {
+			debug!(signal, error = %e, "the interruption handler cannot be installed");
+		}
+	}
+}
+
+/// Whether an interruption was asked for.
+pub fn interrupted() -> bool {
+	INTERRUPTED.get().is_some_and(|flag| flag.load(Ordering::Relaxed))
+}
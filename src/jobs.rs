@@ -0,0 +1,33 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::interrupt;
+
+/// Number of jobs run at once when none is given, as many as the available
+/// CPUs.
+pub fn default_count() -> usize {
+	thread::available_parallelism().map_or(1, usize::from)
+}
+
+/// Runs the given `work` over every one of the `items` on as many threads as
+/// `jobs`, each one taking the next item left as soon as it is done with the
+/// last one, until an interruption is asked for. The results are given in the
+/// order of the items whatever the order they are done in, none for the items
+/// left by an interruption.
+pub fn run<T: Sync, R: Send>(items: &[T], jobs: usize, work: impl Fn(&T) -> R + Sync) -> Vec<Option<R>> {
+	let next = AtomicUsize::new(0);
+	let results = Mutex::new(items.iter().map(|_| None).collect::<Vec<_>>());
+	thread::scope(|scope| {
+		for _ in 0..jobs.clamp(1, items.len().max(1)) {
+			scope.spawn(|| loop {
+				let i = next.fetch_add(1, Ordering::Relaxed);
+				let Some(item) = items.get(i).filter(|_| !interrupt::interrupted()) else { break };
+				let result = work(item);
+				results.lock().unwrap()[i] = Some(result);
+			});
+		}
+	});
+
+	results.into_inner().unwrap()
+}
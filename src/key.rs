@@ -0,0 +1,232 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use rand::Rng as _;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::error::{Error, Result};
+use crate::keyspace::numeric_form;
+use crate::matrix::ModMatrix;
+use crate::process::{DEFAULT_NAMESPACE, suggest};
+
+/// Forms a key can be written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyForm {
+	/// The letters of the key, row by row, like `FJCRXLUDN`
+	Letters,
+	/// The positions of the letters inside the namespace, with the entries
+	/// separated by commas and the rows by semicolons, like `5,9,2;17,23,11;20,3,13`
+	Matrix,
+	/// A [`KeyFile`] as JSON
+	Json,
+}
+
+impl KeyForm {
+	pub const VARIANTS: [&'static str; 3] = ["letters", "matrix", "json"];
+}
+
+impl FromStr for KeyForm {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		match s {
+			"letters" => Ok(KeyForm::Letters),
+			"matrix" => Ok(KeyForm::Matrix),
+			"json" => Ok(KeyForm::Json),
+			_ => Err(format!("unknown key form '{s}'")),
+		}
+	}
+}
+
+/// Structured representation of a key, holding both its letters and its
+/// matrix, along with the namespace relating them if it is not the default one.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyFile {
+	pub key: String,
+	pub matrix: Vec<Vec<usize>>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub namespace: Option<String>,
+}
+
+/// Converts the given `key`, written in the `from` form, to the `to` form,
+/// checking that it is consistent with the given `namespace`, or the default
+/// one. The namespace of a JSON key is used if no other is given. The form is
+/// never guessed, since the letters of a namespace may be digits too.
+pub fn convert(key: &str, namespace: Option<&str>, from: KeyForm, to: KeyForm) -> Result<String> {
+	let key = key.trim();
+	let (numbers, namespace) = match from {
+		KeyForm::Json => {
+			let file: KeyFile = serde_json::from_str(key)
+			.map_err(|e| format!("the JSON key is malformed: {e}"))?;
+			let namespace = match (namespace, file.namespace) {
+				(Some(given), Some(own)) if given != own => {
+					return Err("the JSON key belongs to another namespace".into());
+				},
+				(Some(given), _) => Some(given.to_owned()),
+				(None, own) => own,
+			};
+
+			let chars = namespace_chars(namespace.as_deref());
+			let numbers = from_letters(&file.key, &chars)?;
+			if file.matrix.concat() != numbers {
+				return Err("the letters and the matrix of the JSON key do not match".into());
+			}
+			(numbers, namespace)
+		},
+		KeyForm::Matrix => (from_numbers(key, &namespace_chars(namespace))?, namespace.map(str::to_owned)),
+		KeyForm::Letters => (from_letters(key, &namespace_chars(namespace))?, namespace.map(str::to_owned)),
+	};
+
+	let dimension = numbers.len().isqrt();
+	if dimension * dimension != numbers.len() {
+		return Err(Error::NonSquareKey);
+	}
+
+	let chars = namespace_chars(namespace.as_deref());
+	let letters: String = numbers.iter().map(|&i| chars[i]).collect();
+	match to {
+		KeyForm::Letters => Ok(letters),
+		KeyForm::Matrix => Ok(numeric_form(&numbers, dimension)),
+		KeyForm::Json => {
+			let file = KeyFile {
+				key: letters,
+				matrix: numbers.chunks(dimension.max(1)).map(<[usize]>::to_vec).collect(),
+				namespace,
+			};
+			serde_json::to_string(&file).map_err(|e| e.to_string().into())
+		},
+	}
+}
+
+/// Generates a random key of the given `dimension` whose matrix is invertible
+/// modulo the length of the given `namespace`, or of the default one. The
+/// same `rng` state always gives the same key.
+pub fn generate(dimension: usize, namespace: Option<&str>, rng: &mut StdRng) -> Result<String> {
+	let chars = namespace_chars(namespace);
+	if dimension == 0 {
+		return Err("the dimension of the key must be at least 1".into());
+	}
+	if chars.len() < 2 {
+		return Err(Error::NamespaceTooShort);
+	}
+
+	// the matrices are drawn until an invertible one comes up, which takes
+	// a few draws at most for the usual namespaces
+	loop {
+		let numbers: Vec<_> = (0..dimension * dimension).map(|_| rng.random_range(0..chars.len())).collect();
+		let matrix = ModMatrix::new(dimension, dimension, numbers.clone(), chars.len());
+		if matrix.inverse().is_some() {
+			return Ok(numbers.into_iter().map(|i| chars[i]).collect());
+		}
+	}
+}
+
+/// Reads the key from the file at `path`, trimming the whitespace around it.
+/// A warning is given if any user can read the file.
+pub fn read(path: &Path) -> Result<String> {
+	let key = fs::read_to_string(path)
+		.map_err(|e| format!("the key file '{}' cannot be read: {e}", path.display()))?;
+	if is_world_readable(path) {
+		warn!("the key file '{}' can be read by any user; restrict it with `chmod o-r`", path.display());
+	}
+
+	match key.trim() {
+		"" => Err(Error::EmptyKey),
+		key => Ok(key.to_owned()),
+	}
+}
+
+/// Whether the permissions of the file at `path` let any user read it.
+fn is_world_readable(path: &Path) -> bool {
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt as _;
+
+		fs::metadata(path).is_ok_and(|meta| meta.permissions().mode() & 0o004 != 0)
+	}
+	#[cfg(not(unix))]
+	{
+		let _ = path;
+		false
+	}
+}
+
+/// Retrieves the positions of the given key `letters` inside the namespace.
+fn from_letters(letters: &str, namespace: &[char]) -> Result<Vec<usize>> {
+	letters
+		.chars()
+		.map(|c| {
+			namespace
+				.iter()
+				.position(|&n| n == c.to_ascii_uppercase() || n == c)
+				.ok_or_else(|| Error::UnknownChar(c, suggest(c, namespace)))
+		})
+		.collect()
+}
+
+/// Parses the given key `numbers`, in the numeric form, checking that every
+/// row has as many entries as there are rows, so a single row is only a 1x1
+/// key, and that they are all inside the namespace.
+fn from_numbers(numbers: &str, namespace: &[char]) -> Result<Vec<usize>> {
+	let rows: Vec<Vec<usize>> = numbers
+		.split(';')
+		.map(|row| {
+			row.split(',')
+				.map(|v| v.trim().parse::<usize>().map_err(|_| format!("invalid key entry '{}'", v.trim())))
+				.collect::<std::result::Result<_, _>>()
+		})
+		.collect::<std::result::Result<_, _>>()?;
+
+	if rows.iter().any(|row| row.len() != rows.len()) {
+		return Err("every row of the key must have as many entries as rows the key has".into());
+	}
+	let numbers = rows.concat();
+	if let Some(v) = numbers.iter().find(|&&v| v >= namespace.len()) {
+		return Err(format!("the key entry {v} is out of the namespace, which has {} characters", namespace.len()).into());
+	}
+
+	Ok(numbers)
+}
+
+/// Collects the characters of the given `namespace`, or of the default one.
+fn namespace_chars(namespace: Option<&str>) -> Vec<char> {
+	match namespace {
+		Some(namespace) => namespace.chars().collect(),
+		None => DEFAULT_NAMESPACE.to_vec(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keys_convert_between_their_forms() {
+		let matrix = convert("FJCRXLUDN", None, KeyForm::Letters, KeyForm::Matrix).unwrap();
+		assert_eq!(matrix, "5,9,2;17,23,11;20,3,13");
+		let json = convert(&matrix, None, KeyForm::Matrix, KeyForm::Json).unwrap();
+		assert_eq!(convert(&json, None, KeyForm::Json, KeyForm::Letters).unwrap(), "FJCRXLUDN");
+
+		assert!(convert("5,9;2,40", None, KeyForm::Matrix, KeyForm::Letters).is_err());
+		assert!(convert(r#"{"key":"FJCR","matrix":[[5,9],[2,1]]}"#, None, KeyForm::Json, KeyForm::Letters).is_err());
+
+		// a single row is no square key, and digits may be letters
+		assert!(convert("5,9,2,17", None, KeyForm::Matrix, KeyForm::Letters).is_err());
+		assert_eq!(convert("1234", Some("0123456789"), KeyForm::Letters, KeyForm::Matrix).unwrap(), "1,2;3,4");
+	}
+
+	#[test]
+	fn seeded_keys_are_reproducible_and_invertible() {
+		use crate::sample;
+
+		let generated = generate(4, None, &mut sample::rng(Some(7))).unwrap();
+		assert_eq!(generated, generate(4, None, &mut sample::rng(Some(7))).unwrap());
+		assert_ne!(generated, generate(4, None, &mut sample::rng(Some(8))).unwrap());
+
+		let numbers = generated.chars().map(|c| DEFAULT_NAMESPACE.iter().position(|&d| d == c).unwrap()).collect();
+		assert!(ModMatrix::new(4, 4, numbers, DEFAULT_NAMESPACE.len()).inverse().is_some());
+	}
+}
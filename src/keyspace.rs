@@ -0,0 +1,140 @@
+use std::io;
+
+use crate::error::Result;
+use crate::input::Format;
+use crate::process::{self, DEFAULT_NAMESPACE};
+
+/// An invertible key of a keyspace.
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+	/// Letters of the key, row by row
+	pub key: String,
+	/// Positions of the key's letters inside the namespace, row by row
+	pub numbers: Vec<usize>,
+	/// Determinant of the key's matrix, modulo the namespace length
+	pub determinant: usize,
+}
+
+/// Enumerates the invertible keys of the given `dimension` for the given
+/// `namespace`, or the default one, in lexicographic order of their letters.
+/// The keys are produced lazily, as the keyspace grows exponentially with the
+/// dimension.
+pub fn enumerate(dimension: usize, namespace: Option<&str>) -> Result<impl Iterator<Item = Entry>> {
+	let namespace: Vec<char> = match namespace {
+		Some(namespace) => namespace.chars().collect(),
+		None => DEFAULT_NAMESPACE.to_vec(),
+	};
+	let modulus = namespace.len();
+	if modulus < 2 {
+		return Err("the namespace must have at least 2 characters".into());
+	}
+	let max = process::max_key_dimension(modulus);
+	if dimension == 0 || dimension > max {
+		return Err(format!("the dimension must be between 1 and {max} for this namespace").into());
+	}
+
+	// the positions of the next key, advanced like an odometer
+	let mut next = Some(vec![0; dimension * dimension]);
+	let keys = std::iter::from_fn(move || {
+		let numbers = next.take()?;
+		let mut following = numbers.clone();
+		if let Some(i) = following.iter().rposition(|&v| v + 1 < modulus) {
+			following[i] += 1;
+			following[i + 1..].fill(0);
+			next = Some(following);
+		}
+		Some(numbers)
+	});
+
+	Ok(keys.filter_map(move |numbers| {
+		let determinant = det(&numbers, dimension).rem_euclid(modulus as i128) as usize;
+		if gcd(determinant, modulus) != 1 {
+			return None;
+		}
+
+		Some(Entry {
+			key: numbers.iter().map(|&i| namespace[i]).collect(),
+			numbers,
+			determinant,
+		})
+	}))
+}
+
+/// Renders the given key `numbers` of a key of the given `dimension` in the
+/// numeric form, with its entries separated by commas and its rows by
+/// semicolons, like `5,9;2,17`.
+pub fn numeric_form(numbers: &[usize], dimension: usize) -> String {
+	numbers
+		.chunks(dimension.max(1))
+		.map(|row| row.iter().map(usize::to_string).collect::<Vec<_>>().join(","))
+		.collect::<Vec<_>>()
+		.join(";")
+}
+
+/// Prints the given keyspace `entries` of keys of the given `dimension` to
+/// stdout, as aligned text or as comma or tab separated rows.
+pub fn print(entries: impl Iterator<Item = Entry>, dimension: usize, format: Format) -> Result<()> {
+	let delimiter = match format {
+		Format::Text => {
+			for entry in entries {
+				println!("{}  {}  {}", entry.key, numeric_form(&entry.numbers, dimension), entry.determinant);
+			}
+			return Ok(());
+		},
+		Format::Csv => b',',
+		Format::Tsv => b'\t',
+		_ => return Err("keyspaces can only be printed as text, csv or tsv".into()),
+	};
+
+	let mut writer = csv::WriterBuilder::new()
+		.delimiter(delimiter)
+		.from_writer(io::stdout());
+	writer.write_record(["key", "numbers", "determinant"])
+		.map_err(|e| e.to_string())?;
+	for entry in entries {
+		let numbers = numeric_form(&entry.numbers, dimension);
+		writer.write_record([&entry.key, &numbers, &entry.determinant.to_string()])
+			.map_err(|e| e.to_string())?;
+	}
+
+	writer.flush().map_err(|e| e.to_string().into())
+}
+
+/// Computes the exact determinant of the square matrix with the given row by
+/// row `numbers`, by cofactor expansion along its first row.
+fn det(numbers: &[usize], dimension: usize) -> i128 {
+	if dimension == 1 {
+		return numbers[0] as i128;
+	}
+
+	(0..dimension)
+		.map(|col| {
+			let minor: Vec<_> = numbers[dimension..]
+				.chunks(dimension)
+				.flat_map(|row| row.iter().enumerate().filter(|&(j, _)| j != col).map(|(_, &v)| v))
+				.collect();
+			let sign = if col.is_multiple_of(2) { 1 } else { -1 };
+			sign * numbers[col] as i128 * det(&minor, dimension - 1)
+		})
+		.sum()
+}
+
+/// Computes the greatest common divisor of two numbers.
+fn gcd(a: usize, b: usize) -> usize {
+	if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keyspace_holds_every_invertible_key() {
+		// the known number of invertible 2x2 matrices modulo 26
+		assert_eq!(enumerate(2, None).unwrap().count(), 157_248);
+
+		let entry = enumerate(2, None).unwrap().find(|e| e.key == "HILL").unwrap();
+		assert_eq!(numeric_form(&entry.numbers, 2), "7,8;11,11");
+		assert_eq!(entry.determinant, 15);
+	}
+}
@@ -0,0 +1,74 @@
+//! Ciphering and deciphering of text with the `Hill's Method` cipher.
+//!
+//! The cipher and decipher processes are exposed by the
+//! [`Processor`](process::Processor), configured through its
+//! [`ProcessorBuilder`](process::ProcessorBuilder), which yields a
+//! [`Report`](process::Report) with the result text or an
+//! [`Error`](error::Error) telling why the text could not be processed.
+//!
+//! ```
+//! use hill_cypher::process::ProcessorBuilder;
+//!
+//! let report = ProcessorBuilder::default()
+//!     .key("GYBNQKURP".to_owned())
+//!     .source("ACT".to_owned())
+//!     .fill_letter(None)
+//!     .namespace(None)
+//!     .build()
+//!     .unwrap()
+//!     .cipher()
+//!     .unwrap();
+//!
+//! assert_eq!(report.result_txt, "QRT");
+//! ```
+//!
+//! Reports are always serializable. With the `serde` feature, so are the
+//! processor's settings and the errors, so jobs can be persisted and replayed.
+//!
+//! The rest of the modules back the `hill_cypher` command line application,
+//! and those only meaningful to it are left out of this documentation.
+
+pub mod analysis;
+pub mod archive;
+pub mod audit;
+pub mod checkpoint;
+pub mod config;
+#[doc(hidden)]
+pub mod completions;
+pub mod crack;
+#[doc(hidden)]
+pub mod doctor;
+pub mod error;
+pub mod i18n;
+#[doc(hidden)]
+pub mod input;
+#[doc(hidden)]
+pub mod interrupt;
+#[doc(hidden)]
+pub mod jobs;
+pub mod key;
+pub mod keyspace;
+pub mod matrix;
+pub mod nato;
+pub mod output;
+pub mod parts;
+pub mod process;
+pub mod profile;
+#[doc(hidden)]
+pub mod progress;
+pub mod qr;
+#[doc(hidden)]
+pub mod repl;
+pub mod sample;
+pub mod session;
+pub mod source;
+pub mod stego;
+pub mod stream;
+pub mod table;
+#[doc(hidden)]
+pub mod tui;
+#[doc(hidden)]
+pub mod ui;
+pub mod watch;
+#[doc(hidden)]
+pub mod wizard;
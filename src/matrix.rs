@@ -0,0 +1,324 @@
+use std::fmt;
+use std::ops::Mul;
+
+use zeroize::Zeroize;
+
+/// Matrix of integers modulo a given modulus, that is over the ring `Z_n`,
+/// whose entries are always kept reduced. All of its arithmetic is exact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModMatrix {
+	rows: usize,
+	cols: usize,
+	modulus: usize,
+	/// The entries, row by row
+	data: Vec<usize>,
+}
+
+impl ModMatrix {
+	/// Builds a `rows` x `cols` matrix out of the given row by row `data`,
+	/// reducing every entry modulo `modulus`.
+	///
+	/// # Panics
+	///
+	/// If `data` does not hold `rows` x `cols` entries, or the modulus is 0.
+	pub fn new(rows: usize, cols: usize, data: Vec<usize>, modulus: usize) -> Self {
+		assert_eq!(rows * cols, data.len(), "data does not match the given dimensions");
+		assert!(modulus > 0, "the modulus must be positive");
+
+		let data = data.into_iter().map(|v| v % modulus).collect();
+		Self { rows, cols, modulus, data }
+	}
+
+	/// Builds the `n` x `n` identity matrix modulo `modulus`.
+	pub fn identity(n: usize, modulus: usize) -> Self {
+		let data = (0..n * n).map(|i| usize::from(i % (n + 1) == 0)).collect();
+		Self::new(n, n, data, modulus)
+	}
+
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	pub fn cols(&self) -> usize {
+		self.cols
+	}
+
+	pub fn modulus(&self) -> usize {
+		self.modulus
+	}
+
+	/// Retrieves the entry at the given `row` and `col`.
+	pub fn get(&self, row: usize, col: usize) -> usize {
+		self.data[row * self.cols + col]
+	}
+
+	/// Iterates over the rows of the matrix, as slices of their entries.
+	pub fn row_iter(&self) -> impl Iterator<Item = &[usize]> {
+		(0..self.rows).map(|row| &self.data[row * self.cols..(row + 1) * self.cols])
+	}
+
+	/// Iterates over the entries of the given `col`, from top to bottom.
+	pub fn col(&self, col: usize) -> impl Iterator<Item = usize> + '_ {
+		(0..self.rows).map(move |row| self.get(row, col))
+	}
+
+	/// Retrieves the transpose of the matrix.
+	pub fn transpose(&self) -> Self {
+		let data = (0..self.cols).flat_map(|col| self.col(col)).collect();
+		Self { rows: self.cols, cols: self.rows, modulus: self.modulus, data }
+	}
+
+	/// Retrieves the entries of the matrix, row by row.
+	pub fn into_vec(self) -> Vec<usize> {
+		self.data
+	}
+
+	/// Multiplies the matrix by `other`, leaving the entries of the product
+	/// unreduced, row by row.
+	///
+	/// # Panics
+	///
+	/// If the columns of the matrix are not as many as the rows of `other`.
+	pub fn unreduced_mul(&self, other: &Self) -> Vec<Vec<u128>> {
+		assert_eq!(self.cols, other.rows, "the matrices cannot be multiplied");
+
+		self.row_iter()
+			.map(|row| {
+				(0..other.cols)
+					.map(|col| row.iter().zip(other.col(col)).map(|(&v, w)| v as u128 * w as u128).sum())
+					.collect()
+			})
+			.collect()
+	}
+
+	/// Raises the square matrix to the power of `exp`, by repeated squaring.
+	pub fn pow(&self, exp: usize) -> Self {
+		let mut result = Self::identity(self.rows, self.modulus);
+		let mut base = self.clone();
+		let mut exp = exp;
+		while exp > 0 {
+			if exp % 2 == 1 {
+				result = &result * &base;
+			}
+			base = &base * &base;
+			exp /= 2;
+		}
+
+		result
+	}
+
+	/// Computes the determinant of the square matrix. Over a prime modulus it
+	/// is computed by [elimination](Self::elimination) over the field, and
+	/// otherwise by reducing the matrix to a triangular one with the
+	/// `Euclidean` algorithm, which only subtracts multiples of rows and so
+	/// needs no modular inverse. The determinant of an empty matrix is 1.
+	pub fn det(&self) -> usize {
+		if is_prime(self.modulus) {
+			return self.elimination(self.modulus).0;
+		}
+
+		let n = self.rows;
+		let modulus = self.modulus as i128;
+		let mut rows: Vec<Vec<i128>> = self.row_iter()
+			.map(|row| row.iter().map(|&v| v as i128).collect())
+			.collect();
+
+		let mut det = 1;
+		for k in 0..n {
+			for i in k + 1..n {
+				// clearing the entry below the pivot as the gcd of both is computed
+				while rows[i][k] != 0 {
+					let quotient = rows[k][k] / rows[i][k];
+					let (above, below) = rows.split_at_mut(i);
+					for (v, w) in above[k][k..].iter_mut().zip(&below[0][k..]) {
+						*v = (*v - quotient * w).rem_euclid(modulus);
+					}
+					rows.swap(k, i);
+					det = modulus - det;
+				}
+			}
+			det = det * rows[k][k] % modulus;
+		}
+
+		(det % modulus) as usize
+	}
+
+	/// Computes the modular inverse of the square matrix, if its determinant
+	/// is coprime with the modulus. Over a prime modulus it is computed by
+	/// [elimination](Self::elimination) over the field, and otherwise modulo
+	/// every prime power factor of the modulus, joining the inverses with the
+	/// `Chinese Remainder Theorem`.
+	pub fn inverse(&self) -> Option<Self> {
+		if is_prime(self.modulus) {
+			return self.elimination(self.modulus).1;
+		}
+
+		let modulus = self.modulus as u128;
+		let mut data = vec![0; self.data.len()];
+		for (prime, power) in prime_powers(self.modulus) {
+			let factor = Self::new(self.rows, self.cols, self.data.clone(), power);
+			let factor_inverse = factor.elimination(prime).1?;
+
+			// every residue adds up as the multiple of the rest of the factors
+			// that leaves it modulo this one
+			let rest = self.modulus / power;
+			let weight = rest as u128 * modinverse::modinverse(rest as i128, power as i128).unwrap() as u128;
+			for (entry, v) in data.iter_mut().zip(factor_inverse.data) {
+				*entry = (*entry + weight % modulus * v as u128) % modulus;
+			}
+		}
+
+		Some(Self::new(self.rows, self.cols, data.into_iter().map(|v| v as usize).collect(), self.modulus))
+	}
+
+	/// Computes the determinant of the square matrix and its inverse, if any,
+	/// by `Gauss-Jordan` elimination, for a modulus that is a power of the
+	/// given `prime`; a field when it is the prime itself. The pivots are the
+	/// entries not divisible by the prime, which are the invertible ones, so
+	/// the determinant is only exact when the matrix has an inverse or the
+	/// modulus is prime, and 0 otherwise.
+	fn elimination(&self, prime: usize) -> (usize, Option<Self>) {
+		let n = self.rows;
+		let p = self.modulus as i128;
+
+		// the matrix augmented with the identity
+		let mut rows: Vec<Vec<i128>> = self.row_iter()
+			.enumerate()
+			.map(|(i, row)| row.iter().map(|&v| v as i128).chain((0..n).map(|j| i128::from(i == j))).collect())
+			.collect();
+
+		let mut det = 1 % p;
+		for k in 0..n {
+			let Some(found) = (k..n).find(|&i| rows[i][k] % prime as i128 != 0) else {
+				return (0, None);
+			};
+			if found != k {
+				rows.swap(k, found);
+				det = (p - det) % p;
+			}
+
+			let pivot = rows[k][k];
+			det = det * pivot % p;
+			let pivot_inv = modinverse::modinverse(pivot, p).unwrap();
+			for v in rows[k].iter_mut() {
+				*v = *v * pivot_inv % p;
+			}
+
+			let pivot_row = rows[k].clone();
+			for (_, row) in rows.iter_mut().enumerate().filter(|(i, row)| *i != k && row[k] != 0) {
+				let factor = row[k];
+				for (v, pivot_v) in row.iter_mut().zip(&pivot_row) {
+					*v = (*v - factor * pivot_v).rem_euclid(p);
+				}
+			}
+		}
+
+		let data = rows.into_iter().flat_map(|row| row.into_iter().skip(n).map(|v| v as usize)).collect();
+		(det as usize, Some(Self::new(n, n, data, self.modulus)))
+	}
+}
+
+/// Checks if the supplied number is prime.
+fn is_prime(num: usize) -> bool {
+	num >= 2 && (2..).take_while(|d| d * d <= num).all(|d| !num.is_multiple_of(d))
+}
+
+/// Splits the supplied number into its prime power factors, as pairs of the
+/// prime and its power.
+fn prime_powers(mut num: usize) -> Vec<(usize, usize)> {
+	let mut factors = Vec::new();
+	let mut prime = 2;
+	while num > 1 {
+		if prime * prime > num {
+			prime = num;
+		}
+		let mut power = 1;
+		while num.is_multiple_of(prime) {
+			num /= prime;
+			power *= prime;
+		}
+		if power > 1 {
+			factors.push((prime, power));
+		}
+		prime += 1;
+	}
+
+	factors
+}
+
+impl Zeroize for ModMatrix {
+	/// Wipes the entries of the matrix, as those of a key are secret, keeping
+	/// its dimensions.
+	fn zeroize(&mut self) {
+		self.data.as_mut_slice().zeroize();
+	}
+}
+
+impl Mul for &ModMatrix {
+	type Output = ModMatrix;
+
+	/// Multiplies both matrices, which must share their modulus.
+	fn mul(self, other: Self) -> ModMatrix {
+		assert_eq!(self.modulus, other.modulus, "the matrices have different moduli");
+
+		let modulus = self.modulus as u128;
+		let data = self.unreduced_mul(other)
+			.into_iter()
+			.flatten()
+			.map(|v| (v % modulus) as usize)
+			.collect();
+		ModMatrix::new(self.rows, other.cols, data, self.modulus)
+	}
+}
+
+impl fmt::Display for ModMatrix {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let width = self.data.iter().map(|v| v.to_string().len()).max().unwrap_or(1);
+		for (i, row) in self.row_iter().enumerate() {
+			if i > 0 {
+				writeln!(f)?;
+			}
+			let entries: Vec<_> = row.iter().map(|v| format!("{v:>width$}")).collect();
+			write!(f, "[{}]", entries.join(" "))?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn moduli_are_split_into_prime_powers() {
+		assert!(is_prime(29) && !is_prime(26) && !is_prime(1));
+		assert_eq!(prime_powers(26), [(2, 2), (13, 13)]);
+		assert_eq!(prime_powers(36), [(2, 4), (3, 9)]);
+		assert_eq!(prime_powers(1000), [(2, 8), (5, 125)]);
+	}
+
+	#[test]
+	fn keys_are_inverted_modulo_any_namespace_length() {
+		for (values, modulus, det) in [
+			(vec![6, 24, 1, 13, 16, 10, 20, 17, 15], 29, 6),
+			(vec![6, 24, 1, 13, 16, 10, 20, 17, 15], 26, 25),
+			(vec![3, 3, 2, 5], 26, 9),
+			(vec![2, 1, 1, 1, 2, 1, 1, 1, 3], 36, 7),
+		] {
+			let n = values.len().isqrt();
+			let key = ModMatrix::new(n, n, values, modulus);
+			assert_eq!(key.det(), det);
+			assert_eq!(&key * &key.inverse().unwrap(), ModMatrix::identity(n, modulus));
+		}
+
+		let singular = ModMatrix::new(2, 2, vec![1, 2, 2, 4], 29);
+		assert_eq!(singular.det(), 0);
+		assert_eq!(singular.inverse(), None);
+
+		let singular = ModMatrix::new(2, 2, vec![2, 0, 0, 1], 26);
+		assert_eq!(singular.det(), 2);
+		assert_eq!(singular.inverse(), None);
+		assert_eq!(singular.pow(3).into_vec(), [8, 0, 0, 1]);
+	}
+}
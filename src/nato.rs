@@ -0,0 +1,73 @@
+use crate::error::Result;
+
+/// Words of the NATO phonetic alphabet, for the letters and the digits.
+const WORDS: [(char, &str); 36] = [
+	('A', "Alfa"), ('B', "Bravo"), ('C', "Charlie"), ('D', "Delta"), ('E', "Echo"),
+	('F', "Foxtrot"), ('G', "Golf"), ('H', "Hotel"), ('I', "India"), ('J', "Juliett"),
+	('K', "Kilo"), ('L', "Lima"), ('M', "Mike"), ('N', "November"), ('O', "Oscar"),
+	('P', "Papa"), ('Q', "Quebec"), ('R', "Romeo"), ('S', "Sierra"), ('T', "Tango"),
+	('U', "Uniform"), ('V', "Victor"), ('W', "Whiskey"), ('X', "X-ray"), ('Y', "Yankee"),
+	('Z', "Zulu"), ('0', "Zero"), ('1', "One"), ('2', "Two"), ('3', "Three"),
+	('4', "Four"), ('5', "Five"), ('6', "Six"), ('7', "Seven"), ('8', "Eight"),
+	('9', "Niner"),
+];
+
+/// Common spellings of the words, accepted when reading them.
+const ALIASES: [(char, &str); 5] = [
+	('A', "Alpha"), ('J', "Juliet"), ('X', "Xray"), ('X', "X-Ray"), ('9', "Nine"),
+];
+
+/// Spells the given `text` with the NATO phonetic alphabet, one word per
+/// character and line by line. Characters without a word are kept as they are.
+pub fn encode(text: &str) -> String {
+	text.lines()
+		.map(|line| {
+			line.chars()
+				.map(|c| match WORDS.iter().find(|(letter, _)| *letter == c.to_ascii_uppercase()) {
+					Some((_, word)) => (*word).to_owned(),
+					None => c.to_string(),
+				})
+				.collect::<Vec<_>>()
+				.join(" ")
+		})
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+/// Reads a text spelled with the NATO phonetic alphabet back into its
+/// characters, line by line. The words are matched ignoring their case, and
+/// single characters without a word are kept as they are.
+pub fn decode(text: &str) -> Result<String> {
+	let lines = text
+		.lines()
+		.map(|line| {
+			line.split_whitespace()
+				.map(|word| {
+					WORDS.iter()
+						.chain(&ALIASES)
+						.find(|(_, w)| w.eq_ignore_ascii_case(word))
+						.map(|(c, _)| *c)
+						.or_else(|| {
+							let mut chars = word.chars();
+							chars.next().filter(|c| !c.is_alphanumeric() && chars.next().is_none())
+						})
+						.ok_or_else(|| format!("unknown phonetic word '{word}'").into())
+				})
+				.collect::<Result<String>>()
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn texts_are_spelled_with_the_nato_alphabet() {
+		assert_eq!(encode("WL9\nP-"), "Whiskey Lima Niner\nPapa -");
+		assert_eq!(decode("whiskey LIMA Nine\nalpha -").unwrap(), "WL9\nA-");
+		assert!(decode("Whiskey Lama").is_err());
+	}
+}
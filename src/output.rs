@@ -0,0 +1,336 @@
+use std::fs;
+use std::io::{self, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use encoding_rs::{UTF_16BE, UTF_16LE, UTF_8};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use tracing::debug;
+
+use crate::error::Result;
+use crate::input::{EncodingChoice, NewlineMode};
+use crate::process::{Batch, Report, ReportStyle};
+
+/// Writes the given `text` to the file at `path`, encoded as told by
+/// [`encode`], gzip compressed if asked to, and written with [`write_atomic`].
+pub fn write(
+	path: &Path,
+	text: &str,
+	encoding: EncodingChoice,
+	newlines: NewlineMode,
+	gzip: bool
+) -> Result<()> {
+	debug!(gzip, "writing the output file");
+	let bytes = encode(text, encoding, newlines)?;
+	let bytes = if gzip { self::gzip(&bytes)? } else { bytes };
+
+	write_atomic(path, &bytes, None)
+		.map_err(|e| format!("the output file '{}' cannot be written: {e}", path.display()).into())
+}
+
+/// Replaces the file at `path` with the given `text`, encoded as told by
+/// [`encode`] and written with [`write_atomic`], keeping the permissions of
+/// the original file. The file is first copied to a `.bak` file if a `backup`
+/// is asked for.
+pub fn replace(
+	path: &Path,
+	text: &str,
+	encoding: EncodingChoice,
+	newlines: NewlineMode,
+	backup: bool
+) -> Result<()> {
+	let replace_error = |e: io::Error| format!("the source file '{}' cannot be replaced: {e}", path.display());
+	if path.file_name().is_none() {
+		return Err(format!("'{}' is not a file", path.display()).into());
+	}
+
+	if backup {
+		let backup = with_suffix(path, "", ".bak");
+		debug!(path = %backup.display(), "backing up the source file");
+		fs::copy(path, &backup).map_err(replace_error)?;
+	}
+
+	let bytes = encode(text, encoding, newlines)?;
+	let permissions = fs::metadata(path).map_err(replace_error)?.permissions();
+	write_atomic(path, &bytes, Some(permissions)).map_err(|e| replace_error(e).into())
+}
+
+/// Writes the given `bytes` to the file at `path` through an [`AtomicFile`],
+/// so an interruption never leaves the file half written.
+pub fn write_atomic(path: &Path, bytes: &[u8], permissions: Option<fs::Permissions>) -> io::Result<()> {
+	let mut file = AtomicFile::create(path, permissions)?;
+	file.write_all(bytes)?;
+	file.commit()
+}
+
+/// A file written through a temporary one in the same directory, which is
+/// synced to disk and renamed over it once committed. The temporary file is
+/// removed if it is dropped before, like when the writing fails or is
+/// interrupted.
+pub struct AtomicFile {
+	file: fs::File,
+	path: PathBuf,
+	tmp: PathBuf,
+	committed: bool,
+}
+
+impl AtomicFile {
+	/// Creates the temporary file of the one at `path`. The given
+	/// `permissions` are set before anything is written, so the bytes are
+	/// never exposed with looser ones.
+	pub fn create(path: &Path, permissions: Option<fs::Permissions>) -> io::Result<Self> {
+		let tmp = with_suffix(path, ".", ".tmp");
+		let file = AtomicFile {
+			file: fs::File::create(&tmp)?,
+			path: path.to_owned(),
+			tmp,
+			committed: false,
+		};
+		if let Some(permissions) = permissions {
+			file.file.set_permissions(permissions)?;
+		}
+		Ok(file)
+	}
+
+	/// Syncs the written bytes to disk and renames the temporary file over
+	/// the target one.
+	pub fn commit(mut self) -> io::Result<()> {
+		self.file.sync_all()?;
+		fs::rename(&self.tmp, &self.path)?;
+		self.committed = true;
+
+		// the rename itself is made durable by syncing the directory holding it
+		#[cfg(unix)]
+		if let Some(dir) = self.path.parent().map(|dir| if dir.as_os_str().is_empty() { Path::new(".") } else { dir }) {
+			fs::File::open(dir).and_then(|dir| dir.sync_all()).ok();
+		}
+
+		Ok(())
+	}
+}
+
+impl Write for AtomicFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.file.write(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.file.flush()
+	}
+}
+
+impl Drop for AtomicFile {
+	fn drop(&mut self) {
+		if !self.committed {
+			fs::remove_file(&self.tmp).ok();
+		}
+	}
+}
+
+/// Permissions letting only the owner read and write a file, where the
+/// platform has them.
+pub fn private_permissions() -> Option<fs::Permissions> {
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt as _;
+		Some(fs::Permissions::from_mode(0o600))
+	}
+	#[cfg(not(unix))]
+	None
+}
+
+/// Builds the path of a file next to the one at `path`, with the given
+/// `prefix` and `suffix` around its name.
+fn with_suffix(path: &Path, prefix: &str, suffix: &str) -> PathBuf {
+	let mut name = std::ffi::OsString::from(prefix);
+	name.push(path.file_name().unwrap_or_default());
+	name.push(suffix);
+	path.with_file_name(name)
+}
+
+/// Writes the whole `report` to the file at `path`, serialized after the
+/// path's extension: `json`, `yaml`, `yml` or `toml`. Any other extension
+/// gets the report rendered as plain `label: value` lines.
+pub fn write_report(path: &Path, report: &Report) -> Result<()> {
+	let contents = match path.extension().and_then(|ext| ext.to_str()) {
+		Some("json") => report.to_json()? + "\n",
+		Some("yaml" | "yml") => report.to_yaml()?,
+		Some("toml") => report.to_toml()?,
+		_ => report.render(ReportStyle::Verbose) + "\n",
+	};
+	write_report_file(path, &contents)
+}
+
+/// Writes the whole `batch` to the file at `path`, serialized after the path's
+/// extension like [`write_report`]. Any other extension gets the batch
+/// rendered as plain text.
+pub fn write_batch_report(path: &Path, batch: &Batch) -> Result<()> {
+	let contents = match path.extension().and_then(|ext| ext.to_str()) {
+		Some("json") => batch.to_json()? + "\n",
+		Some("yaml" | "yml") => batch.to_yaml()?,
+		Some("toml") => batch.to_toml()?,
+		_ => batch.render() + "\n",
+	};
+	write_report_file(path, &contents)
+}
+
+/// Writes the given report `contents` to the file at `path`.
+fn write_report_file(path: &Path, contents: &str) -> Result<()> {
+	debug!(path = %path.display(), "writing the report file");
+
+	write_atomic(path, contents.as_bytes(), None)
+		.map_err(|e| format!("the report file '{}' cannot be written: {e}", path.display()).into())
+}
+
+/// Destination of results written as they are produced, either a file or
+/// stdout, encoded as told by [`encode`].
+pub struct Sink {
+	writer: Writer,
+	encoding: EncodingChoice,
+	newlines: NewlineMode,
+	written: u64,
+}
+
+enum Writer {
+	Stdout(io::Stdout),
+	File(fs::File),
+	Atomic(AtomicFile),
+	Gzip(GzEncoder<AtomicFile>),
+}
+
+impl Sink {
+	/// Opens the file at `path`, gzip compressed if asked to, or stdout if no
+	/// path is given. The file is written as an [`AtomicFile`], so it is only
+	/// replaced once the output is [finished](Self::finish).
+	pub fn open(
+		path: Option<&Path>,
+		encoding: EncodingChoice,
+		newlines: NewlineMode,
+		gzip: bool
+	) -> Result<Self> {
+		let writer = match path {
+			Some(path) => {
+				let file = AtomicFile::create(path, None).map_err(|e| {
+					format!("the output file '{}' cannot be written: {e}", path.display())
+				})?;
+				if gzip {
+					Writer::Gzip(GzEncoder::new(file, Compression::default()))
+				} else {
+					Writer::Atomic(file)
+				}
+			},
+			None => Writer::Stdout(io::stdout()),
+		};
+
+		Ok(Sink { writer, encoding, newlines, written: 0 })
+	}
+
+	/// Opens the file at `path` to go on writing to it after its first `len`
+	/// bytes, dropping anything written after them.
+	pub fn resume(
+		path: &Path,
+		len: u64,
+		encoding: EncodingChoice,
+		newlines: NewlineMode
+	) -> Result<Self> {
+		let output_error = |e: io::Error| format!("the output file '{}' cannot be written: {e}", path.display());
+		let mut file = fs::OpenOptions::new()
+			.create(true)
+			.truncate(false)
+			.write(true)
+			.open(path)
+			.map_err(output_error)?;
+		file.set_len(len)
+			.and_then(|_| file.seek(io::SeekFrom::End(0)))
+			.map_err(output_error)?;
+
+		Ok(Sink { writer: Writer::File(file), encoding, newlines, written: len })
+	}
+
+	/// Number of bytes written so far.
+	pub fn written(&self) -> u64 {
+		self.written
+	}
+
+	/// Writes the given `text` right away.
+	pub fn write(&mut self, text: &str) -> Result<()> {
+		let bytes = encode(text, self.encoding, self.newlines)?;
+		let writer: &mut dyn Write = match &mut self.writer {
+			Writer::Stdout(stdout) => stdout,
+			Writer::File(file) => file,
+			Writer::Atomic(file) => file,
+			Writer::Gzip(encoder) => encoder,
+		};
+
+		writer.write_all(&bytes)
+			.and_then(|_| writer.flush())
+			.map_err(|e| format!("the output cannot be written: {e}"))?;
+		self.written += bytes.len() as u64;
+		Ok(())
+	}
+
+	/// Completes the output, finishing its compression if any and committing
+	/// its file. A sink dropped before leaves the file as it was.
+	pub fn finish(self) -> Result<()> {
+		match self.writer {
+			Writer::Atomic(file) => file.commit().map_err(|e| format!("the output cannot be written: {e}"))?,
+			Writer::Gzip(encoder) => encoder
+				.finish()
+				.map_err(|e| format!("the output cannot be compressed: {e}"))?
+				.commit()
+				.map_err(|e| format!("the output cannot be written: {e}"))?,
+			Writer::Stdout(_) | Writer::File(_) => (),
+		}
+		Ok(())
+	}
+}
+
+/// Encodes the given `text` with the given `encoding`, with its line
+/// terminators converted according to the given `newlines` mode.
+/// [`EncodingChoice::Auto`] stands for UTF-8.
+pub fn encode(text: &str, encoding: EncodingChoice, newlines: NewlineMode) -> Result<Vec<u8>> {
+	let text = convert_newlines(text, newlines);
+	let encoding = match encoding {
+		EncodingChoice::Auto => UTF_8,
+		EncodingChoice::Label(encoding) => encoding,
+	};
+	debug!(encoding = encoding.name(), ?newlines, "encoding the result text");
+
+	// the UTF-16 encoders of the standard only produce UTF-8, so those are
+	// encoded by hand
+	if encoding == UTF_16LE {
+		return Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect());
+	}
+	if encoding == UTF_16BE {
+		return Ok(text.encode_utf16().flat_map(u16::to_be_bytes).collect());
+	}
+
+	let (bytes, _, unmappable) = encoding.encode(&text);
+	if unmappable {
+		return Err(format!(
+			"the result text cannot be represented in {}",
+			encoding.name()
+		).into());
+	}
+	Ok(bytes.into_owned())
+}
+
+/// Compresses the given `bytes` with gzip.
+pub fn gzip(bytes: &[u8]) -> Result<Vec<u8>> {
+	let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+	encoder.write_all(bytes)
+		.and_then(|_| encoder.finish())
+		.map_err(|e| format!("the output cannot be compressed: {e}").into())
+}
+
+/// Converts the line terminators of the given `text` to the ones of the given
+/// `mode`, leaving them as they are for the rest of the modes.
+fn convert_newlines(text: &str, mode: NewlineMode) -> String {
+	let terminator = match mode {
+		NewlineMode::Lf => "\n",
+		NewlineMode::Crlf => "\r\n",
+		_ => return text.to_owned(),
+	};
+
+	text.replace("\r\n", "\n").replace('\n', terminator)
+}
@@ -0,0 +1,89 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Result;
+
+/// Tag that starts the header line of every part of a message.
+const HEADER_TAG: &str = "HILL-PART";
+
+/// Position of a part inside a message split across several transmissions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Part {
+	/// Position of the part, counted from 1
+	pub index: usize,
+	/// Number of parts of the message
+	pub total: usize,
+}
+
+impl fmt::Display for Part {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}", self.index, self.total)
+	}
+}
+
+impl FromStr for Part {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		let invalid = || format!("invalid part '{s}', it must look like 2/5");
+		let (index, total) = s.split_once('/').ok_or_else(invalid)?;
+		let index: usize = index.trim().parse().map_err(|_| invalid())?;
+		let total: usize = total.trim().parse().map_err(|_| invalid())?;
+
+		if index == 0 || index > total {
+			return Err(format!("invalid part '{s}', it must be between 1 and {total}"));
+		}
+		Ok(Part { index, total })
+	}
+}
+
+/// Prepends the header line of the given `part` to a `text`.
+pub fn with_header(part: Part, text: &str) -> String {
+	format!("{HEADER_TAG} {part}\n{text}")
+}
+
+/// Splits a part's `text` into its header and its body. The single line
+/// terminator that may end the body is dropped.
+fn split(text: &str) -> Result<(Part, &str)> {
+	let (header, body) = text.split_once('\n').unwrap_or((text, ""));
+	let part = header
+		.trim_end_matches('\r')
+		.strip_prefix(HEADER_TAG)
+		.ok_or("the text has no part header")?
+		.trim()
+		.parse::<Part>()?;
+	let body = body.strip_suffix('\n').map_or(body, |b| b.strip_suffix('\r').unwrap_or(b));
+
+	Ok((part, body))
+}
+
+/// Joins the bodies of the given part `texts` into the whole message, after
+/// checking that all of them belong to the same message and that none of its
+/// parts is missing or repeated. The parts may be given in any order.
+pub fn join(texts: &[String]) -> Result<String> {
+	let mut parts = texts
+		.iter()
+		.map(|text| split(text))
+		.collect::<Result<Vec<_>>>()?;
+	parts.sort_by_key(|(part, _)| part.index);
+	if parts.is_empty() {
+		return Err("no parts were supplied".into());
+	}
+
+	let total = parts.first().map_or(0, |(part, _)| part.total);
+	if let Some((part, _)) = parts.iter().find(|(part, _)| part.total != total) {
+		return Err(format!("the part {part} belongs to a message of {} parts, not {total}", part.total).into());
+	}
+	for (expected, (part, _)) in (1..).zip(&parts) {
+		if part.index != expected {
+			let problem = if part.index < expected { "repeated" } else { "missing" };
+			let index = if part.index < expected { part.index } else { expected };
+			return Err(format!("the part {index}/{total} is {problem}").into());
+		}
+	}
+	if parts.len() != total {
+		return Err(format!("the part {}/{total} is missing", parts.len() + 1).into());
+	}
+
+	Ok(parts.into_iter().map(|(_, body)| body).collect())
+}
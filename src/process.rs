@@ -1,9 +1,20 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+
 use derive_builder::Builder;
 use fancy_regex::Regex;
-use rulinalg::matrix::{Matrix, BaseMatrix};
+use serde::{Deserialize, Serialize};
 use modinverse;
+use tracing::{debug, trace};
+use unicode_normalization::{Decompositions, IsNormalized, Recompositions, UnicodeNormalization as _};
+use unicode_normalization::char::{canonical_combining_class, is_combining_mark};
+use zeroize::Zeroizing;
 
-use crate::error::Result;
+use crate::error::{Error, Result, Suggestion};
+use crate::i18n::{self, Msg};
+use crate::matrix::ModMatrix;
 
 /// Default namespace used by the `cipher` and `decipher` algorithms to do its
 /// work. This value is obscured if a `custom namespace` is specified.
@@ -12,30 +23,538 @@ pub const DEFAULT_NAMESPACE: [char; 26] = [
 	'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z'
 ];
 
+/// Namespace presets that can be picked by name instead of typing a whole
+/// `custom namespace`. A preset without value stands for the default namespace.
+pub const NAMESPACE_PRESETS: [(&str, Option<&str>); 4] = [
+	("default", None),
+	("alphanumeric", Some("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789")),
+	("symbols", Some("ABCDEFGHIJKLMNOPQRSTUVWXYZ @$^&*/?.-")),
+	("filename", Some(FILENAME_NAMESPACE)),
+];
+
+/// Namespace of the characters that are safe in file names on every platform,
+/// which file names are ciphered over.
+pub const FILENAME_NAMESPACE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
 /// `Cipher`/`Decipher` processes report.
 ///
 /// A report that holds the results of the processes performed by a
 /// [`Processor`] with the information provided to program.
-#[derive(Debug, Default, Builder, PartialEq)]
+#[derive(Debug, Default, Builder, PartialEq, Serialize, Deserialize)]
 pub struct Report {
 	pub used_key: String,
+	/// Key equivalent to applying the used key as many times as asked, if
+	/// more than once
+	#[builder(default)]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub effective_key: Option<String>,
 	pub source_txt: String,
 	pub fill_letter: Option<char>,
 	pub result_txt: String,
 	pub filled: bool,
-	pub def_namespace: Option<String>
+	/// Positions of the fill letters appended to the source text, counted
+	/// from 0 among the processed characters
+	#[builder(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub padding: Vec<usize>,
+	pub def_namespace: Option<String>,
+	/// Dimension of the key's square matrix representation
+	#[builder(default)]
+	pub key_dimension: usize,
+	/// Number of blocks the processed text was split into
+	#[builder(default)]
+	pub block_count: usize,
+	/// Version of the tool that produced the report
+	#[builder(default = "env!(\"CARGO_PKG_VERSION\").to_owned()")]
+	pub version: String,
+	/// RFC 3339 timestamp of when the process started
+	#[builder(default)]
+	pub started_at: String,
+	/// RFC 3339 timestamp of when the process finished
+	#[builder(default)]
+	pub finished_at: String,
+	/// Step by step computations of every block, if they were traced
+	#[builder(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub blocks: Vec<BlockTrace>,
+	/// Blocks that could not be processed, if they were recovered from
+	#[builder(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub failed_blocks: Vec<BlockFailure>,
+}
+
+/// A block of the source text that could not be processed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockFailure {
+	/// Position of the block, counted from 0
+	pub index: usize,
+	/// Why the block could not be processed
+	pub reason: String,
+}
+
+/// What is done with the blocks of a source text that cannot be processed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BadBlocks {
+	/// The whole process fails
+	#[default]
+	Fail,
+	/// The blocks are left out of the result
+	Skip,
+	/// The blocks are replaced by the given character in the result
+	Placeholder(char),
+}
+
+impl std::str::FromStr for BadBlocks {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		match s.split_once(':') {
+			None if s == "fail" => Ok(BadBlocks::Fail),
+			None if s == "skip" => Ok(BadBlocks::Skip),
+			Some(("placeholder", c)) if c.chars().count() == 1 => {
+				Ok(BadBlocks::Placeholder(c.chars().next().unwrap()))
+			},
+			_ => Err(format!("unknown bad blocks policy '{s}', use fail, skip or placeholder:<char>")),
+		}
+	}
+}
+
+/// Unicode normalization forms the texts can be put in before processing
+/// them, so that the same character written with different code points, like
+/// a precomposed `é` and an `e` followed by a combining accent, is matched.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Normalization {
+	/// Composed characters
+	#[default]
+	Nfc,
+	/// Decomposed characters
+	Nfd,
+	/// The texts are left as they are
+	None,
+}
+
+impl Normalization {
+	pub const VARIANTS: [&'static str; 3] = ["nfc", "nfd", "none"];
+
+	/// Puts the given `text` in the normalization form.
+	pub fn apply(self, text: &str) -> String {
+		match self {
+			Normalization::Nfc => text.nfc().collect(),
+			Normalization::Nfd => text.nfd().collect(),
+			Normalization::None => text.to_owned(),
+		}
+	}
+
+	/// Retrieves the length of the start of the given `text` that can be put
+	/// in the normalization form on its own, whatever follows it: the text up
+	/// to its last character nothing before can combine with.
+	pub fn stable_len(self, text: &str) -> usize {
+		if self == Normalization::None {
+			return text.len();
+		}
+
+		let stable = |c: char| {
+			canonical_combining_class(c) == 0
+				&& unicode_normalization::is_nfc_quick(std::iter::once(c)) == IsNormalized::Yes
+		};
+		text.char_indices().rev().find(|&(_, c)| stable(c)).map_or(0, |(i, _)| i)
+	}
+
+	/// Puts the given `chars` in the normalization form as they are read.
+	fn chars<I: Iterator<Item = char>>(self, chars: I) -> Normalized<I> {
+		match self {
+			Normalization::Nfc => Normalized::Nfc(chars.nfc()),
+			Normalization::Nfd => Normalized::Nfd(chars.nfd()),
+			Normalization::None => Normalized::None(chars),
+		}
+	}
+}
+
+/// Characters of an iterator put in a normalization form as they are read.
+enum Normalized<I: Iterator<Item = char>> {
+	Nfc(Recompositions<I>),
+	Nfd(Decompositions<I>),
+	None(I),
+}
+
+impl<I: Iterator<Item = char>> Iterator for Normalized<I> {
+	type Item = char;
+
+	fn next(&mut self) -> Option<char> {
+		match self {
+			Normalized::Nfc(chars) => chars.next(),
+			Normalized::Nfd(chars) => chars.next(),
+			Normalized::None(chars) => chars.next(),
+		}
+	}
+}
+
+impl std::str::FromStr for Normalization {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		match s {
+			"nfc" => Ok(Normalization::Nfc),
+			"nfd" => Ok(Normalization::Nfd),
+			"none" => Ok(Normalization::None),
+			_ => Err(format!("unknown normalization form '{s}'")),
+		}
+	}
+}
+
+/// How the characters out of the namespace are folded into it, so the same
+/// letter matches whatever its case. The characters found in the namespace as
+/// they are are always kept, so case-sensitive namespaces are honored.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CaseFolding {
+	/// Characters are folded into their single uppercase character, with the
+	/// German `ß` folded into the capital `ẞ`
+	#[default]
+	Upper,
+	/// As [`CaseFolding::Upper`], with the Turkish dotted `i` folded into `İ`
+	/// and the dotless `ı` into `I`
+	Turkish,
+	/// Characters are matched exactly
+	None,
+}
+
+impl CaseFolding {
+	pub const VARIANTS: [&'static str; 3] = ["upper", "turkish", "none"];
+
+	/// Folds the given `text` into the `namespace`, character by character.
+	pub fn apply(self, text: &str, namespace: &HashSet<char>) -> String {
+		text.chars().map(|c| self.fold(c, namespace)).collect()
+	}
+
+	/// Folds the given character into the `namespace`: it is kept if it is in
+	/// the namespace, or if its folded character is not.
+	pub fn fold(self, c: char, namespace: &HashSet<char>) -> char {
+		if self == CaseFolding::None || namespace.contains(&c) {
+			return c;
+		}
+
+		let folded = match (self, c) {
+			(CaseFolding::Turkish, 'i') => 'İ',
+			(CaseFolding::Turkish, 'ı') => 'I',
+			(_, 'ß') => 'ẞ',
+			_ => {
+				let mut upper = c.to_uppercase();
+				match (upper.next(), upper.next()) {
+					(Some(upper), None) => upper,
+					_ => c,
+				}
+			},
+		};
+		if namespace.contains(&folded) { folded } else { c }
+	}
+}
+
+impl std::str::FromStr for CaseFolding {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		match s {
+			"upper" => Ok(CaseFolding::Upper),
+			"turkish" => Ok(CaseFolding::Turkish),
+			"none" => Ok(CaseFolding::None),
+			_ => Err(format!("unknown case folding '{s}'")),
+		}
+	}
+}
+
+/// Computations performed over a single block of a processed text.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockTrace {
+	/// Characters of the block
+	pub input: String,
+	/// Positions of the block's characters inside the namespace
+	pub input_indices: Vec<usize>,
+	/// Product of the key matrix by the block's positions, before reducing it
+	pub product: Vec<i64>,
+	/// Product reduced modulo the namespace length
+	pub output_indices: Vec<usize>,
+	/// Characters the reduced positions stand for
+	pub output: String,
+}
+
+/// Styles a [`Report`] can be rendered with.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ReportStyle {
+	/// Only the result text, and whether the source text was filled
+	Compact,
+	/// Every field of the report
+	#[default]
+	Verbose,
+}
+
+impl Report {
+	/// Retrieves the fields shown by the given rendering `style`, as pairs of
+	/// the field's label and its value.
+	pub fn fields(&self, style: ReportStyle) -> Vec<(Msg, String)> {
+		let namespace = match &self.def_namespace {
+			Some(ns) => ns.clone(),
+			None => i18n::tr(Msg::DefaultNamespace).to_owned(),
+		};
+
+		match style {
+			ReportStyle::Compact => {
+				let mut fields = vec![(Msg::ResultText, self.result_txt.clone())];
+				if self.filled {
+					fields.push((Msg::Filled, i18n::tr_bool(self.filled).to_owned()));
+				}
+				fields
+			},
+			ReportStyle::Verbose => {
+				let mut fields = vec![
+					(Msg::UsedKey, self.used_key.clone()),
+					(Msg::SourceText, self.source_txt.clone()),
+					(Msg::ResultText, self.result_txt.clone()),
+					(Msg::Filled, i18n::tr_bool(self.filled).to_owned()),
+					(Msg::Namespace, namespace),
+					(Msg::KeyDimension, self.key_dimension.to_string()),
+					(Msg::BlockCount, self.block_count.to_string()),
+					(Msg::Version, self.version.clone()),
+					(Msg::StartedAt, self.started_at.clone()),
+					(Msg::FinishedAt, self.finished_at.clone()),
+				];
+				if let Some(key) = &self.effective_key {
+					fields.insert(1, (Msg::EffectiveKey, key.clone()));
+				}
+				fields
+			},
+		}
+	}
+
+	/// Serializes the report as pretty printed JSON, the canonical schema
+	/// shared by every structured output of the report.
+	pub fn to_json(&self) -> Result<String> {
+		serde_json::to_string_pretty(self).map_err(|e| e.to_string().into())
+	}
+
+	/// Serializes the report as YAML, following the [`Report::to_json`] schema.
+	pub fn to_yaml(&self) -> Result<String> {
+		serde_yaml::to_string(self).map_err(|e| e.to_string().into())
+	}
+
+	/// Serializes the report as TOML, following the [`Report::to_json`] schema.
+	pub fn to_toml(&self) -> Result<String> {
+		toml::to_string_pretty(self).map_err(|e| e.to_string().into())
+	}
+
+	/// Renders the traced blocks of the report as a markdown table holding,
+	/// per block, the source letters and vector, and the result vector and
+	/// letters.
+	pub fn block_table(&self) -> String {
+		let vector = |indices: &[usize]| {
+			indices.iter().map(usize::to_string).collect::<Vec<_>>().join(" ")
+		};
+		let header = [Msg::SourceText, Msg::SourceVector, Msg::ResultVector, Msg::ResultText]
+			.map(|msg| i18n::tr(msg).to_owned());
+		let rows: Vec<[String; 4]> = self.blocks
+			.iter()
+			.map(|block| [
+				block.input.clone(),
+				vector(&block.input_indices),
+				vector(&block.output_indices),
+				block.output.clone(),
+			])
+			.collect();
+
+		let mut widths = header.clone().map(|h| h.chars().count());
+		for row in &rows {
+			for (width, cell) in widths.iter_mut().zip(row) {
+				*width = (*width).max(cell.chars().count());
+			}
+		}
+		let line = |cells: &[String; 4]| {
+			let cells = cells
+				.iter()
+				.zip(widths)
+				.map(|(cell, width)| format!("{cell:width$}"))
+				.collect::<Vec<_>>();
+			format!("| {} |", cells.join(" | "))
+		};
+		let rule = format!("|{}|", widths.map(|width| "-".repeat(width + 2)).join("|"));
+
+		[line(&header), rule]
+			.into_iter()
+			.chain(rows.iter().map(line))
+			.collect::<Vec<_>>()
+			.join("\n")
+	}
+
+	/// Renders the report as unstyled `label: value` lines in the current
+	/// language, showing the fields of the given `style`.
+	pub fn render(&self, style: ReportStyle) -> String {
+		self.fields(style)
+			.into_iter()
+			.map(|(label, value)| format!("{}: {value}", i18n::tr(label)))
+			.collect::<Vec<_>>()
+			.join("\n")
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.render(ReportStyle::Verbose))
+	}
+}
+
+/// Aggregated report of several source texts processed with the same settings.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Batch {
+	pub items: Vec<BatchItem>,
+	pub summary: BatchSummary,
+}
+
+/// A processed source text of a [`Batch`], with its report or the error it
+/// failed with.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchItem {
+	/// The source text
+	pub input: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub report: Option<Report>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// Counts of the items of a [`Batch`].
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchSummary {
+	pub processed: usize,
+	pub failed: usize,
+	pub filled: usize,
+}
+
+impl Batch {
+	/// Collects the given pairs of inputs and their outcomes, counting them.
+	pub fn new(outcomes: Vec<(String, Result<Report>)>) -> Self {
+		let mut summary = BatchSummary::default();
+		let items = outcomes
+			.into_iter()
+			.map(|(input, outcome)| {
+				summary.processed += 1;
+				match outcome {
+					Ok(report) => {
+						summary.filled += usize::from(report.filled);
+						BatchItem { input, report: Some(report), error: None }
+					},
+					Err(e) => {
+						summary.failed += 1;
+						BatchItem { input, report: None, error: Some(i18n::tr_error(&e)) }
+					},
+				}
+			})
+			.collect();
+
+		Self { items, summary }
+	}
+
+	/// Serializes the batch as pretty printed JSON.
+	pub fn to_json(&self) -> Result<String> {
+		serde_json::to_string_pretty(self).map_err(|e| e.to_string().into())
+	}
+
+	/// Renders the batch as plain text, a numbered `input -> outcome` line for
+	/// every item followed by the summary.
+	pub fn render(&self) -> String {
+		let mut lines: Vec<_> = self.items
+			.iter()
+			.enumerate()
+			.map(|(i, item)| {
+				let outcome = item.report.as_ref().map_or(item.error.as_deref().unwrap_or_default(), |r| &r.result_txt);
+				format!("{:>3}. {} -> {outcome}", i + 1, item.input)
+			})
+			.collect();
+		lines.push(format!("{}: {} {}, {} {}, {} {}",
+			i18n::tr(Msg::Summary),
+			self.summary.processed, i18n::tr(Msg::Processed),
+			self.summary.failed, i18n::tr(Msg::Failed),
+			self.summary.filled, i18n::tr(Msg::FilledCount)
+		));
+
+		lines.join("\n")
+	}
+
+	/// Serializes the batch as YAML, following the [`Batch::to_json`] schema.
+	pub fn to_yaml(&self) -> Result<String> {
+		serde_yaml::to_string(self).map_err(|e| e.to_string().into())
+	}
+
+	/// Serializes the batch as TOML, following the [`Batch::to_json`] schema.
+	pub fn to_toml(&self) -> Result<String> {
+		toml::to_string_pretty(self).map_err(|e| e.to_string().into())
+	}
 }
 
 /// A `Cipher` and `Decipher` processor.
 ///
 /// The processor exposes the application's cipher and decipher capabilities
 /// based on the `Hill's Method` cipher.
+///
+/// A configured processor holds no mutable state, so it is `Send` and `Sync`
+/// and can be shared through an `Arc` to process many texts concurrently with
+/// [`Processor::cipher_blocks`] and [`Processor::decipher_blocks`].
+///
+/// The key, along with its matrices, is wiped from memory once the processor
+/// is dropped. With the `serde` feature, its settings can be serialized to be
+/// replayed later.
 #[derive(Debug, Default, Builder)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Processor {
-	key: String,
+	#[builder(setter(into))]
+	#[cfg_attr(feature = "serde", serde(with = "secret"))]
+	key: Zeroizing<String>,
 	source: String,
 	fill_letter: Option<char>,
 	namespace: Option<String>,
+	/// Whether the computations of every block are recorded in the report
+	#[builder(default)]
+	trace_blocks: bool,
+	/// What is done with the blocks that cannot be processed
+	#[builder(default)]
+	bad_blocks: BadBlocks,
+	/// Largest key dimension accepted, any if not given
+	#[builder(default)]
+	max_key_dimension: Option<usize>,
+	/// Number of times the key is applied to the source text
+	#[builder(default = "1")]
+	iterations: usize,
+	/// Form the key, source text and namespace are normalized to
+	#[builder(default)]
+	normalization: Normalization,
+	/// How the characters of the key, source text and fill letter are folded
+	/// into the namespace
+	#[builder(default)]
+	case_folding: CaseFolding,
+	/// Number of threads the blocks of large texts are processed on with the
+	/// `rayon` feature, as many as the available CPUs if 0
+	#[builder(default)]
+	jobs: usize,
+	/// Key matrices already set up, shared with the processors made out of
+	/// this one
+	#[builder(setter(skip))]
+	#[cfg_attr(feature = "serde", serde(skip))]
+	setup: Arc<KeySetup>,
+}
+
+/// Matrix the source text's blocks are multiplied by, along with the
+/// effective key.
+type PreparedKey = (Zeroizing<ModMatrix>, Option<String>);
+
+/// Key matrices set up for the `cipher` and `decipher` processes.
+#[derive(Debug, Default)]
+struct KeySetup {
+	cipher: OnceLock<PreparedKey>,
+	decipher: OnceLock<PreparedKey>,
+	/// Threads the blocks of large texts are processed on, the global ones if
+	/// `None`
+	#[cfg(feature = "rayon")]
+	pool: OnceLock<Option<rayon::ThreadPool>>,
 }
 
 impl Processor {
@@ -43,6 +562,131 @@ impl Processor {
 	/// to the program, like a `key`, a `fill letter` or a possibe
 	/// `custom namespace`.
 	pub fn cipher(self) -> Result<Report> {
+		crate::stage!("cipher", self.recovering(Self::cipher_all))
+	}
+
+	/// Deciphers the given `ciphertext` based on the information passed
+	/// to the program, like the known `key`, or a possible known `fill letter`
+	/// and a `custom namespace` used in the `cipher` process.
+	pub fn decipher(self) -> Result<Report> {
+		crate::stage!("decipher", self.recovering(Self::decipher_all))
+	}
+
+	/// Makes a processor of the given `source` text with the same settings,
+	/// which reuses the key matrices this one has already set up, so many
+	/// texts are processed with the key validated only once.
+	pub fn with_source(&self, source: String) -> Processor {
+		Processor {
+			key: self.key.clone(),
+			source,
+			fill_letter: self.fill_letter,
+			namespace: self.namespace.clone(),
+			trace_blocks: self.trace_blocks,
+			bad_blocks: self.bad_blocks,
+			max_key_dimension: self.max_key_dimension,
+			iterations: self.iterations,
+			normalization: self.normalization,
+			case_folding: self.case_folding,
+			jobs: self.jobs,
+			setup: Arc::clone(&self.setup),
+		}
+	}
+
+	/// Lazily ciphers the given `chars` block by block, as the iterator is
+	/// advanced, using the processor's settings; its own `source text` is
+	/// ignored. The last partial block is filled. After an error, the iterator
+	/// ends.
+	pub fn cipher_blocks<I: Iterator<Item = char>>(&self, chars: I) -> Blocks<'_, I> {
+		Blocks::new(self, chars, Self::cipher)
+	}
+
+	/// Lazily deciphers the given `chars` block by block, as
+	/// [`Processor::cipher_blocks`] does.
+	pub fn decipher_blocks<I: Iterator<Item = char>>(&self, chars: I) -> Blocks<'_, I> {
+		Blocks::new(self, chars, Self::decipher)
+	}
+
+	/// Performs the given `process` over the blocks of the source text that
+	/// can be processed, leaving out the ones with unknown characters as told
+	/// by the [`BadBlocks`] policy, which are recorded in the report.
+	fn recovering(mut self, process: fn(Processor) -> Result<Report>) -> Result<Report> {
+		// the characters written with different code points are matched
+		let form = self.normalization;
+		self.key = Zeroizing::new(form.apply(&self.key));
+		self.source = form.apply(&self.source);
+		self.namespace = self.namespace.map(|ns| form.apply(&ns));
+
+		// so are the ones written in another case; a malformed namespace is
+		// reported by the process
+		if let Ok(namespace) = self.def_namespace() {
+			let namespace: HashSet<_> = namespace.into_iter().collect();
+			let folding = self.case_folding;
+			self.key = Zeroizing::new(folding.apply(&self.key, &namespace));
+			self.source = folding.apply(&self.source, &namespace);
+			self.fill_letter = self.fill_letter.map(|c| folding.fold(c, &namespace));
+		}
+
+		let policy = self.bad_blocks;
+		let namespace = match policy {
+			BadBlocks::Fail => return process(self),
+			_ => self.def_namespace()?,
+		};
+		let dimension = key_dimension(&self.key);
+		if dimension == 0 {
+			return process(self);
+		}
+
+		let chars: Vec<_> = self.source.chars().collect();
+		let failures: Vec<_> = chars
+			.chunks(dimension)
+			.enumerate()
+			.filter_map(|(index, block)| {
+				let c = block.iter().find(|c| !namespace.contains(c))?;
+				let error = Error::UnknownChar(*c, suggest(*c, &namespace));
+				Some(BlockFailure { index, reason: i18n::tr_error(&error) })
+			})
+			.collect();
+		if failures.is_empty() {
+			return process(self);
+		}
+		debug!(failed = failures.len(), "leaving out the blocks that cannot be processed");
+
+		let failed = |index| failures.iter().any(|f: &BlockFailure| f.index == index);
+		let good: String = chars
+			.chunks(dimension)
+			.enumerate()
+			.filter(|(index, _)| !failed(*index))
+			.flat_map(|(_, block)| block)
+			.collect();
+		let source = std::mem::replace(&mut self.source, good);
+		let mut report = if self.source.is_empty() {
+			self.build_report(String::new(), false, dimension, SystemTime::now())
+		} else {
+			process(self)?
+		};
+
+		// putting the results of the processed blocks back in place, along
+		// with the placeholders of the failed ones
+		let result: Vec<_> = report.result_txt.chars().collect();
+		let mut processed = result.chunks(dimension);
+		let mut result_txt = String::with_capacity(report.result_txt.len());
+		for (index, block) in chars.chunks(dimension).enumerate() {
+			if !failed(index) {
+				result_txt.extend(processed.next().into_iter().flatten());
+			} else if let BadBlocks::Placeholder(c) = policy {
+				result_txt.extend(std::iter::repeat_n(c, block.len()));
+			}
+		}
+
+		report.source_txt = source;
+		report.result_txt = result_txt;
+		report.failed_blocks = failures;
+		Ok(report)
+	}
+
+	fn cipher_all(self) -> Result<Report> {
+		let started = SystemTime::now();
+
 		// definition of which namespace to use: either the user supplied
 		// namespace or the default one
 		let namespace = self.def_namespace()?;
@@ -51,57 +695,60 @@ impl Processor {
 		self.check_information(&namespace)?;
 
 		// getting the checked key's length square root
-		let dimension = (self.key.len() as f64).sqrt() as usize;
+		let dimension = key_dimension(&self.key);
+		debug!(dimension, "key dimension computed");
 
 		// checking if the source text's length is divisible by the above dimension.
 		// If it is not, the the text is filled
 		let mut was_filled = false;
-		let sl = self.source.len();
-		let source = if !is_divisble(self.source.len(), &dimension) {
+		let sl = self.source.chars().count();
+		let source = crate::stage!("padding", if !is_divisble(sl, &dimension) {
 			was_filled = true;
+			let filled_len = turn_divisible(sl, &dimension);
+			debug!(
+				source_len = sl,
+				filled_len,
+				fill_letter = ?self.fill_letter,
+				"source text is not divisible by the key dimension, filling it"
+			);
 			fill_txt(
 				&self.source,
-				self.fill_letter.unwrap(),
-				turn_divisible(sl, &dimension), sl
+				self.fill_letter.ok_or(Error::MissingFillLetter)?,
+				filled_len, sl
 			)
 		} else {
-			self.source.to_uppercase()
+			self.source.clone()
+		});
+
+		let (key_mtrx_repr, effective_key) = crate::stage!("matrix setup", self.prepared_key(&namespace, false)?);
+
+		// the whole source text is only turned into a matrix to be traced
+		let blocks = if self.trace_blocks {
+			let src_mtrx_repr = txt_mtrx_repr(source.chars().count() / dimension, dimension, &source, &namespace)?;
+			trace!(matrix = %src_mtrx_repr, "source text matrix representation");
+			trace_blocks(&key_mtrx_repr, &src_mtrx_repr, &namespace)
+		} else {
+			Vec::new()
 		};
 
-		// getting the key's matrix representation and its determinant
-		let key_mtrx_repr = txt_mtrx_repr(dimension, dimension, &self.key, &namespace)?;
-		let key_mtrx_det = key_mtrx_repr.clone().det(); // it is clone because det() consumes
-													  // the the receiver
-
-		// checking if the supplied key's matrix representation is valid to
-		// use for the cipher process
-		Self::check_key_mtrx_validness(&key_mtrx_det, namespace.len())?;
-
-		// spliting the source text into as many parts as the square root of
-		// the key's matrix representation dimension, and turning its values
-		// into its respective numeric representation inside the namespace
-		let src_mtrx_repr = txt_mtrx_repr(
-			source.len() / dimension,
-			dimension,
-			&source,
-			&namespace
-		)?;
-
-		// turning the ciphertext parts into its textual representation
-		let ciphered_txt = translate_txt_mtrx(
-			&key_mtrx_repr,
-			src_mtrx_repr,
-			namespace
-		);
+		// turning the source text into the ciphertext block by block
+		let ciphered_txt = self.translate(&key_mtrx_repr, &source, &namespace)?;
+		debug!(blocks = source.chars().count() / dimension, "source text ciphered");
+
+		// the fill letters are appended after every character of the source text
+		let padding = (self.source.chars().count()..source.chars().count()).collect();
 
 		// building the report
-		Ok(self.build_report(ciphered_txt, was_filled))
+		let mut report = self.build_report(ciphered_txt, was_filled, dimension, started);
+		report.blocks = blocks;
+		report.padding = padding;
+		report.effective_key = effective_key;
+		Ok(report)
 	}
 
-	/// Deciphers the given `ciphertext` based on the information passed
-	/// to the program, like the known `key`, or a possible known `fill letter`
-	/// and a `custom namespace` used in the `cipher` process.
-	pub fn decipher(self) -> Result<Report> {
+	fn decipher_all(self) -> Result<Report> {
+		let started = SystemTime::now();
+
 		// definition of which namespace to use: either the user supplied
 		// namespace or the default one
 		let namespace = self.def_namespace()?;
@@ -110,133 +757,211 @@ impl Processor {
 		self.check_information(&namespace)?;
 
 		// getting the passed key's length square root
-		let dimension = (self.key.len() as f64).sqrt() as usize;
-
-		// getting the key's matrix representation and its inverse
-		let key_mtrx_repr = txt_mtrx_repr(dimension, dimension, &self.key, &namespace)?;
-		let key_mtrx_inv = key_mtrx_repr.clone().inverse();
-
-		// deciphering the given source text
-		match key_mtrx_inv {
-			Ok(inverse) => {
-				let key_mtrx_det = key_mtrx_repr.det();
-				
-				// checking if the supplied key's matrix representation is valid to
-				// use for the decipher process
-				Self::check_key_mtrx_validness(&key_mtrx_det, namespace.len())?;
-
-				// getting modular multiplicative inverse of the keys's
-				// matrix representation determinant
-				let mod_mul_inv = modinverse::modinverse(
-					key_mtrx_det as i128,
-					namespace.len() as i128
-				).unwrap() as f64;
-				
-				// multipling the key's matrix representation inverse
-				// by its modular multiplicative inverse
-				let inverse = Matrix::new(
-					inverse.rows(),
-					inverse.cols(),
-					inverse
-						.into_vec()
-						.into_iter()
-						.map(|v| ((v * mod_mul_inv) * key_mtrx_det).round())
-						.collect::<Vec<_>>()
-				);
+		let dimension = key_dimension(&self.key);
+		debug!(dimension, "key dimension computed");
 
-				// turning the ciphertext into its matrix representation
-				let src_mtrx_repr = txt_mtrx_repr(
-					self.source.len() / dimension,
-					dimension,
-					&self.source,
-					&namespace
-				)?;
-
-				// turning the deciphertext parts into its textual representation
-				let deciphered_txt = translate_txt_mtrx(
-					&inverse,
-					src_mtrx_repr,
-					namespace,
-				);
+		let (inverse, effective_key) = crate::stage!("matrix setup", self.prepared_key(&namespace, true)?);
 
-				// building the report
-				Ok(self.build_report(deciphered_txt, false))
-			},
-			// if the passed key's matrix representation has no an inverse,
-			// then the key length is not square
-			Err(_) => Err(
-				"invalid or malformed key. the key has no a square length".into()
-			)
+		// the whole ciphertext is only turned into a matrix to be traced
+		let blocks = if self.trace_blocks {
+			let rows = self.source.chars().count() / dimension;
+			let src_mtrx_repr = txt_mtrx_repr(rows, dimension, &self.source, &namespace)?;
+			trace_blocks(&inverse, &src_mtrx_repr, &namespace)
+		} else {
+			Vec::new()
+		};
+
+		// turning the ciphertext into the deciphertext block by block
+		let deciphered_txt = self.translate(&inverse, &self.source, &namespace)?;
+
+		debug!(blocks = self.source.chars().count() / dimension, "source text deciphered");
+
+		// building the report
+		let mut report = self.build_report(deciphered_txt, false, dimension, started);
+		report.blocks = blocks;
+		report.effective_key = effective_key;
+		Ok(report)
+	}
+
+	/// Retrieves the matrix the source text's blocks are multiplied by, made
+	/// out of the key's matrix representation, or out of its modular inverse
+	/// if `inverted`, along with the effective key. They are set up only once
+	/// for all the processors made out of the same one with
+	/// [`Processor::with_source`].
+	fn prepared_key(&self, namespace: &[char], inverted: bool) -> Result<PreparedKey> {
+		let cache = if inverted { &self.setup.decipher } else { &self.setup.cipher };
+		if let Some(prepared) = cache.get() {
+			trace!("reusing the key matrix already set up");
+			return Ok(prepared.clone());
+		}
+
+		let dimension = key_dimension(&self.key);
+		let key_mtrx_repr = Zeroizing::new(txt_mtrx_repr(dimension, dimension, &self.key, namespace)?);
+		trace!(matrix = %*key_mtrx_repr, "key matrix representation");
+		let matrix = if inverted {
+			let inverse = Zeroizing::new(key_mtrx_repr.inverse().ok_or(Error::InvalidKeyMatrix(namespace.len()))?);
+			trace!(matrix = %*inverse, "key matrix modular inverse");
+
+			// undoing many applications of the key is the same as applying the
+			// power of its inverse once
+			inverse.pow(self.iterations)
+		} else {
+			// checking if the supplied key's matrix representation is valid to
+			// use for the cipher process
+			let key_mtrx_det = key_mtrx_repr.det();
+			debug!(determinant = key_mtrx_det, "key matrix determinant computed");
+			Self::check_key_mtrx_validness(key_mtrx_det, namespace.len())?;
+
+			// applying the key many times is the same as applying its power once
+			key_mtrx_repr.pow(self.iterations)
+		};
+
+		let effective_key = self.effective_key(&key_mtrx_repr, namespace);
+		Ok(cache.get_or_init(|| (Zeroizing::new(matrix), effective_key)).clone())
+	}
+
+	/// Retrieves the key equivalent to applying the one with the given matrix
+	/// representation as many times as asked, if more than once.
+	fn effective_key(&self, key_mtrx: &ModMatrix, namespace: &[char]) -> Option<String> {
+		if self.iterations == 1 {
+			return None;
+		}
+
+		let composed = key_mtrx.pow(self.iterations);
+		trace!(matrix = %composed, iterations = self.iterations, "composed key matrix");
+		// the matrix representation holds the key's letters column by column
+		Some(composed.transpose().into_vec().into_iter().map(|v| namespace[v]).collect())
+	}
+
+	/// Multiplies every block of the given `text` by the `key_mtrx`, on as
+	/// many threads as the jobs if the text is large enough and the `rayon`
+	/// feature is enabled.
+	fn translate(&self, key_mtrx: &ModMatrix, text: &str, namespace: &[char]) -> Result<String> {
+		#[cfg(feature = "rayon")]
+		if self.jobs != 1 && text.len() >= PARALLEL_THRESHOLD {
+			let pool = self.setup.pool.get_or_init(|| thread_pool(self.jobs));
+			return translate_parallel(key_mtrx, text, namespace, pool.as_ref());
+		}
+		translate_blocks(key_mtrx, text, namespace)
+	}
+
+	/// Retrieves the `key`'s matrix representation inside the namespace in
+	/// use, that is the matrix the `cipher` process multiplies the source
+	/// text's parts by. If the key cannot be represented, an
+	/// [`Error`](crate::error::Error) is returned.
+	pub fn key_matrix(&self) -> Result<Zeroizing<ModMatrix>> {
+		let namespace = self.def_namespace()?;
+
+		if self.key.is_empty() {
+			return Err(Error::EmptyKey)
 		}
+		if !is_square(self.key.chars().count()) {
+			return Err(Error::NonSquareKey)
+		}
+		for c in self.key.chars() {
+			Self::is_in_namespace(c, &namespace)?;
+		}
+
+		let dimension = key_dimension(&self.key);
+		txt_mtrx_repr(dimension, dimension, &self.key, &namespace).map(Zeroizing::new)
 	}
 
 	/// Builds a final `Report` instance that hold the result of the `cipher`
-	/// or `decipher` processes.
-	pub fn build_report(self, res_text: String, filled: bool) -> Report {
+	/// or `decipher` processes, which were performed with a key of the given
+	/// `dimension` and `started` at the given time.
+	pub fn build_report(
+		self,
+		res_text: String,
+		filled: bool,
+		dimension: usize,
+		started: SystemTime
+	) -> Report {
+		let block_count = res_text.chars().count() / dimension;
+
 		ReportBuilder::default()
-		   .used_key(self.key)
+		   .used_key(self.key.to_string())
 		   .source_txt(self.source)
 		   .result_txt(res_text)
 		   .fill_letter(self.fill_letter)
 		   .filled(filled)
 		   .def_namespace(self.namespace)
+		   .key_dimension(dimension)
+		   .block_count(block_count)
+		   .started_at(humantime::format_rfc3339_millis(started).to_string())
+		   .finished_at(humantime::format_rfc3339_millis(SystemTime::now()).to_string())
 		   .build()
 		   .unwrap()
 	}
 
 	/// Defines the `namespace` to use in the `cipher` and `decipher` processes.
 	/// If a custom namespace is not defined, the default one is used. In case
-	/// that the user defined namespace has a length < 29, then
-	/// (ProcessingError)[crate::error::Error] is returned.
+	/// that the user defined namespace has less than two characters,
+	/// (NamespaceTooShort)[crate::error::Error::NamespaceTooShort] is returned.
 	fn def_namespace(&self) -> Result<Vec<char>> {
 		match &self.namespace {
 			Some(ns) => {
 				// cheking if the supplied namespace is malformed
-				Self::check_namespace(&ns)?;
-
-				if !is_square(ns.len()) {
-					return Err(
-						format!(
-							"the supplied namespace must be square in length"
-						).into()
-					);
+				Self::check_namespace(ns)?;
+
+				// a single character namespace reduces everything to it
+				let len = ns.chars().count();
+				if len < 2 {
+					debug!(len, "custom namespace rejected, it is too short");
+					return Err(Error::NamespaceTooShort);
 				}
+				debug!(len, "using a custom namespace");
 				Ok(ns.chars().collect())
 			},
-			None => Ok(DEFAULT_NAMESPACE.to_vec())
+			None => {
+				debug!(len = DEFAULT_NAMESPACE.len(), "using the default namespace");
+				Ok(DEFAULT_NAMESPACE.to_vec())
+			}
 		}
 	}
 
 	/// Checks if possible custom `defined` namespace is malformed, that is
 	/// if it has duplicated values, if it is the case,
-	/// (ProcessingError)[crate::error::Error] is returned.
-	fn check_namespace(namespace: &String) -> Result<()> {
+	/// (DuplicatedNamespaceChars)[crate::error::Error::DuplicatedNamespaceChars]
+	/// is returned.
+	fn check_namespace(namespace: &str) -> Result<()> {
 		let rgx = Regex::new(r"(.)\1{1,}").unwrap();
 		if rgx.is_match(namespace).unwrap() {
-			return Err("the supplied namespace has duplicated characters".into())
+			debug!("custom namespace rejected, it has consecutive duplicated characters");
+			return Err(Error::DuplicatedNamespaceChars)
 		}
 
 		Ok(())
 	}
 
 	/// Checks the validness of the user supplied information. If something went
-	/// wrong in the checking, (ProcessingError)[crate::error::Error] is returned.
+	/// wrong in the checking, an [`Error`](crate::error::Error) is returned.
 	fn check_information(&self, namespace: &[char]) -> Result<()> {
 		// checking if the supplied key has a square length
-		if !is_square(self.key.len()) {
-			return Err("the supplied key must be square in length".into())
+		if self.key.is_empty() {
+			return Err(Error::EmptyKey)
+		}
+		if !is_square(self.key.chars().count()) {
+			debug!(len = self.key.chars().count(), "key rejected, its length is not square");
+			return Err(Error::NonSquareKey)
+		}
+
+		// checking if the key's dimension is within the given limit, if any
+		let dimension = key_dimension(&self.key);
+		if let Some(max) = self.max_key_dimension.filter(|&max| dimension > max) {
+			debug!(dimension, max, "key rejected, its dimension is too large");
+			return Err(Error::KeyTooLarge { dimension, max });
 		}
 
 		// checking if the supplied fill character is inside the namespace
 		if let Some(f) = self.fill_letter {
-			Self::is_in_namespace(f, &namespace)?;
+			Self::is_in_namespace(f, namespace)?;
 		}
 
 		// checking if the supplied key and source text have an unkwnon character
-		let mut target = &self.key;
+		let mut target: &String = &self.key;
 		for _ in 0..2 {
 			for c in target.chars() {
-				Self::is_in_namespace(c, &namespace)?;
+				Self::is_in_namespace(c, namespace)?;
 			}
 			target = &self.source;
 		}
@@ -246,70 +971,430 @@ impl Processor {
 
 	/// Checks if the supplied `key`'s matrix representation is valid to perform
 	/// the `cipher` and `decipher` processes, if it is not,
-	/// (ProcessingError)[crate::error::Error] is returned.
-	fn check_key_mtrx_validness(det: &f64, ns_len: usize) -> Result<()> {
-		let mod_mul_inv = modinverse::modinverse(*det as i128, ns_len as i128);
-		if *det == 0.0 || mod_mul_inv.is_none() || has_any_factor(det.abs() as usize, ns_len) {
-			return Err(
-				format!(
-					"the specified key cannot be used. [matrix's det 0 or has factors with {}]",
-					ns_len
-				 ).into()
-			)
+	/// (InvalidKeyMatrix)[crate::error::Error::InvalidKeyMatrix] is returned.
+	fn check_key_mtrx_validness(det: usize, ns_len: usize) -> Result<()> {
+		let mod_mul_inv = modinverse::modinverse(det as i128, ns_len as i128);
+		debug!(
+			determinant = det,
+			modulus = ns_len,
+			inverse = ?mod_mul_inv,
+			"checking the key matrix validness"
+		);
+
+		if mod_mul_inv.is_none() {
+			debug!("key rejected, its matrix has no modular inverse");
+			return Err(Error::InvalidKeyMatrix(ns_len))
 		}
 
 		Ok(())
 	}
 
 	/// Checks if the supplied `character` is inside the given namespace; if it
-	/// is not, (ProcessingError)[crate::error::Error] is returned.
+	/// is not, (UnknownChar)[crate::error::Error::UnknownChar] is returned.
 	fn is_in_namespace(char: char, namespace: &[char]) -> Result<()> {
-		if namespace.into_iter().find(|&c| *c == char) == None {
-			return Err(
-				format!(
-					"the character '{char}' is not present in the namespace"
-				).into()
-			);
+		trace!(char = %char, "checking the character is inside the namespace");
+		if !namespace.contains(&char) {
+			debug!(char = %char, "character rejected, it is not inside the namespace");
+			return Err(Error::UnknownChar(char, suggest(char, namespace)));
 		}
 
 		Ok(())
 	}
 }
 
-/// Turns a given (Matrix)[rulinalg::matrix::Matrix] filled with the positions
-/// of each character of any `text`, into its textual
-/// representations inside the supplied namespace; all using another
-/// (Matrix)[rulinalg::matrix::Matrix] as key for the process.
-fn translate_txt_mtrx(
-	key_mtrx: &Matrix<f64>,
-	src_mtrx: Matrix<f64>,
-	namespace: Vec<char>
-) -> String {
-	// ciphering the source text's matrix
-	let mtrx_mul = (key_mtrx * src_mtrx).transpose();
-	mtrx_mul
-		.into_vec()
+/// Iterator over the result of processing the characters of another
+/// iterator, one block at a time. Created by [`Processor::cipher_blocks`] and
+/// [`Processor::decipher_blocks`].
+pub struct Blocks<'a, I: Iterator<Item = char>> {
+	processor: &'a Processor,
+	/// Characters already normalized, so the blocks are counted as they are
+	/// processed
+	chars: Normalized<I>,
+	process: fn(Processor) -> Result<Report>,
+	dimension: usize,
+	/// Result characters of the last processed block not yielded yet
+	pending: std::vec::IntoIter<char>,
+	done: bool,
+}
+
+impl<'a, I: Iterator<Item = char>> Blocks<'a, I> {
+	fn new(processor: &'a Processor, chars: I, process: fn(Processor) -> Result<Report>) -> Self {
+		Blocks {
+			processor,
+			chars: processor.normalization.chars(chars),
+			process,
+			dimension: key_dimension(&processor.key).max(1),
+			pending: Vec::new().into_iter(),
+			done: false,
+		}
+	}
+}
+
+impl<I: Iterator<Item = char>> Iterator for Blocks<'_, I> {
+	type Item = Result<char>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(c) = self.pending.next() {
+				return Some(Ok(c));
+			}
+			if self.done {
+				return None;
+			}
+
+			let block: String = self.chars.by_ref().take(self.dimension).collect();
+			if block.is_empty() {
+				self.done = true;
+				return None;
+			}
+			trace!(block = %block, "processing the next block");
+
+			let processor = Processor { trace_blocks: false, ..self.processor.with_source(block) };
+			match (self.process)(processor) {
+				Ok(report) => self.pending = report.result_txt.chars().collect::<Vec<_>>().into_iter(),
+				Err(e) => {
+					self.done = true;
+					return Some(Err(e));
+				},
+			}
+		}
+	}
+}
+
+/// Serialization of the key as a plain string, which is wrapped again to be
+/// wiped from memory once deserialized.
+#[cfg(feature = "serde")]
+mod secret {
+	use serde::{Deserialize, Deserializer, Serializer};
+	use zeroize::Zeroizing;
+
+	pub fn serialize<S: Serializer>(key: &Zeroizing<String>, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(key)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Zeroizing<String>, D::Error> {
+		String::deserialize(deserializer).map(Zeroizing::new)
+	}
+}
+
+/// Resolves a namespace given either by the name of one of the
+/// [`NAMESPACE_PRESETS`] or as a custom one. `None` stands for the default
+/// namespace.
+pub fn namespace_from(namespace: &str) -> Option<String> {
+	match NAMESPACE_PRESETS.iter().find(|(name, _)| *name == namespace) {
+		Some((_, preset)) => preset.map(str::to_owned),
+		None if namespace.is_empty() => None,
+		None => Some(namespace.to_owned()),
+	}
+}
+
+/// Builds a namespace out of the distinct characters of the given `texts`,
+/// along with the ones of the `base` namespace if given, sorted by their code
+/// points so the same texts always give the same namespace. Since namespaces
+/// have at least two characters, a shorter one is completed with the first
+/// printable ASCII characters left out.
+pub fn auto_namespace(base: Option<&str>, texts: &[&str]) -> String {
+	let chars: BTreeSet<char> = base
+		.unwrap_or_default()
+		.chars()
+		.chain(texts.iter().flat_map(|text| text.chars()))
+		.collect();
+	let mut namespace: String = chars.iter().collect();
+
+	let mut len = chars.len();
+	let mut unused = (' '..='~').filter(|c| !chars.contains(c));
+	while len < 2 {
+		match unused.next() {
+			Some(c) => namespace.push(c),
+			None => break,
+		}
+		len += 1;
+	}
+	debug!(len, "namespace built out of the texts");
+
+	namespace
+}
+
+/// Looks for a likely fix of the character `c` missing from the `namespace`:
+/// the same letter in another case, or without its accents, or the hint to
+/// compose a combining mark.
+pub fn suggest(c: char, namespace: &[char]) -> Option<Suggestion> {
+	if is_combining_mark(c) {
+		return Some(Suggestion::Normalize);
+	}
+	let cases = |c: char| [Some(c), one(c.to_uppercase()), one(c.to_lowercase())];
+	if let Some(other) = cases(c).into_iter().skip(1).flatten().find(|other| namespace.contains(other)) {
+		return Some(Suggestion::Case(other));
+	}
+
+	let base = one(c.nfd().filter(|&c| !is_combining_mark(c))).filter(|&base| base != c)?;
+	cases(base)
 		.into_iter()
-		.map(|v| namespace[euc_mod(v as i128, namespace.len() as u128) as usize])
+		.flatten()
+		.find(|other| namespace.contains(other))
+		.map(Suggestion::Unaccented)
+}
+
+/// Retrieves the single item of the given `items`, if there is exactly one.
+fn one<T>(mut items: impl Iterator<Item = T>) -> Option<T> {
+	match (items.next(), items.next()) {
+		(Some(item), None) => Some(item),
+		_ => None,
+	}
+}
+
+/// Re-maps every character of the given `text` found in the `from` namespace
+/// into the character at the same position of the `to` namespace, leaving the
+/// rest as they are.
+pub fn transcode(text: &str, from: &[char], to: &[char]) -> String {
+	text.chars()
+		.map(|c| from.iter().position(|&f| f == c).and_then(|i| to.get(i).copied()).unwrap_or(c))
+		.collect()
+}
+
+/// Largest key dimension of the keys listed with their determinant for a
+/// namespace of `ns_len` characters. The Hadamard's bound keeps their whole
+/// determinant under 2^53, so its cofactor expansion stays far from the range
+/// of `i128` and the keyspace from growing past any practical listing.
+pub fn max_key_dimension(ns_len: usize) -> usize {
+	let bound = 2f64.powi(53);
+	let entry = ns_len.saturating_sub(1).max(1) as f64;
+
+	(1..)
+		.take_while(|&dim| ((dim as f64).sqrt() * entry).powi(dim) < bound)
+		.last()
+		.unwrap_or(1) as usize
+}
+
+/// Explains why the given `err` happened when processing with the given `key`
+/// and `namespace`, as lines telling what was computed and what would be
+/// valid instead. Errors that tell everything by themselves give no lines.
+pub fn explain(err: &Error, key: &str, namespace: Option<&str>) -> Vec<String> {
+	let namespace: Vec<char> = namespace.map_or(DEFAULT_NAMESPACE.to_vec(), |ns| ns.chars().collect());
+	let squares = |len: usize| {
+		let root = (len as f64).sqrt() as usize;
+		format!("like {} or {}", root * root, (root + 1) * (root + 1))
+	};
+
+	match err {
+		Error::InvalidKeyMatrix(n) => {
+			// the key is folded into the namespace as it was processed
+			let key = CaseFolding::default().apply(key, &namespace.iter().copied().collect());
+			if key.chars().any(|c| !namespace.contains(&c)) {
+				return Vec::new();
+			}
+			let dimension = key_dimension(&key);
+			let Ok(mtrx) = txt_mtrx_repr(dimension, dimension, &key, &namespace) else {
+				return Vec::new();
+			};
+			let reduced = mtrx.det();
+			let valid: Vec<_> = (1..*n).filter(|&d| gcd(d, *n) == 1).collect();
+			let listed = valid.iter().take(24).map(usize::to_string).collect::<Vec<_>>().join(", ");
+			let more = if valid.len() > 24 { ", ..." } else { "" };
+
+			let mut lines = vec![
+				format!("the key's matrix has a determinant of {reduced} modulo {n}"),
+				format!(
+					"its greatest common divisor with {n} is {}, so it has no inverse modulo {n}",
+					gcd(reduced, *n)
+				),
+				format!("a key is invertible when its determinant modulo {n} is one of: {listed}{more}"),
+			];
+			// only 2x2 keys can be enumerated
+			if dimension == 2 {
+				lines.push("changing a single letter of the key changes its determinant; `keyspace enumerate` \
+					lists the valid 2x2 keys".to_owned());
+			}
+			lines
+		},
+		Error::NonSquareKey => vec![format!(
+			"the key has {} characters, but its matrix needs a square number of them, {}",
+			key.chars().count(),
+			squares(key.chars().count())
+		)],
+		_ => Vec::new(),
+	}
+}
+
+/// Computes the greatest common divisor of two numbers.
+fn gcd(a: usize, b: usize) -> usize {
+	if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Comparison of two texts, character by character.
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+	/// Position of the first differing character, if the texts differ
+	pub first_difference: Option<usize>,
+	/// Number of positions where both texts hold the same character
+	pub matching: usize,
+	/// Length of the longest text
+	pub length: usize,
+}
+
+impl Comparison {
+	/// Compares the `expected` text against the `actual` one. Characters
+	/// missing from the shortest text count as differences.
+	pub fn new(expected: &str, actual: &str) -> Self {
+		let expected: Vec<_> = expected.chars().collect();
+		let actual: Vec<_> = actual.chars().collect();
+		let length = expected.len().max(actual.len());
+
+		let equal = |i: usize| expected.get(i).is_some() && expected.get(i) == actual.get(i);
+		Comparison {
+			first_difference: (0..length).find(|&i| !equal(i)),
+			matching: (0..length).filter(|&i| equal(i)).count(),
+			length,
+		}
+	}
+
+	/// Percentage of the positions where both texts match. Two empty texts
+	/// match completely.
+	pub fn percentage(&self) -> f64 {
+		if self.length == 0 {
+			return 100.0;
+		}
+		self.matching as f64 * 100.0 / self.length as f64
+	}
+}
+
+/// Number of blocks of a text turned into positions inside the namespace at
+/// once, before being multiplied.
+const BATCH_BLOCKS: usize = 4096;
+
+/// Multiplies every block of the given `text`, as the positions of its
+/// characters inside the supplied namespace, by the `key_mtrx`, turning the
+/// products back into characters. The text is processed a batch of blocks at a
+/// time, so no matrix of the whole of it is ever built. A text that does not
+/// split into whole blocks is rejected with
+/// [`PartialBlock`](crate::error::Error::PartialBlock).
+fn translate_blocks(key_mtrx: &ModMatrix, text: &str, namespace: &[char]) -> Result<String> {
+	let modulus = namespace.len() as u128;
+	let dimension = key_mtrx.cols().max(1);
+	let mut chars = text.chars().peekable();
+	let mut positions = Vec::with_capacity(dimension * BATCH_BLOCKS);
+	let mut result = String::with_capacity(text.len());
+
+	while chars.peek().is_some() {
+		crate::stage!("encoding", {
+			positions.clear();
+			positions.extend(chars.by_ref().take(dimension * BATCH_BLOCKS).map(|c| char_pos(c, namespace) as u128));
+		});
+		if !positions.len().is_multiple_of(dimension) {
+			return Err(Error::PartialBlock { len: text.chars().count(), dimension });
+		}
+
+		// every entry of the result block is a row of the key times the block
+		crate::stage!("multiplication", for block in positions.chunks_exact(dimension) {
+			for row in key_mtrx.row_iter() {
+				let v: u128 = row.iter().zip(block).map(|(&k, &s)| k as u128 * s).sum();
+				result.push(namespace[(v % modulus) as usize]);
+			}
+		});
+	}
+
+	Ok(result)
+}
+
+/// Size of the texts, in bytes, from which their blocks are processed in
+/// parallel.
+#[cfg(feature = "rayon")]
+const PARALLEL_THRESHOLD: usize = 1024 * 1024;
+
+/// Builds the pool of as many threads as the given `jobs`, once for every text
+/// a processor and the ones made out of it process. None is built if 0 or if
+/// it fails, so the global pool of as many threads as the available CPUs is
+/// used instead.
+#[cfg(feature = "rayon")]
+fn thread_pool(jobs: usize) -> Option<rayon::ThreadPool> {
+	if jobs == 0 {
+		return None;
+	}
+
+	debug!(jobs, "building the thread pool");
+	rayon::ThreadPoolBuilder::new()
+		.num_threads(jobs)
+		.build()
+		.inspect_err(|e| debug!(error = %e, "no thread pool could be built, using the global one"))
+		.ok()
+}
+
+/// Multiplies the blocks of the given `text` like [`translate_blocks`], split
+/// into chunks processed on the threads of the given `pool`, or of the global
+/// one if none is given.
+#[cfg(feature = "rayon")]
+fn translate_parallel(
+	key_mtrx: &ModMatrix,
+	text: &str,
+	namespace: &[char],
+	pool: Option<&rayon::ThreadPool>
+) -> Result<String> {
+	use rayon::prelude::*;
+
+	// the chunks hold whole blocks, so none is split between two threads
+	let chunk_len = key_mtrx.cols().max(1) * 16 * 1024;
+	let mut bounds: Vec<_> = text.char_indices().step_by(chunk_len).map(|(i, _)| i).collect();
+	bounds.push(text.len());
+	debug!(chunks = bounds.len() - 1, "processing the blocks in parallel");
+
+	let work = || {
+		bounds
+			.par_windows(2)
+			.map(|bounds| translate_blocks(key_mtrx, &text[bounds[0]..bounds[1]], namespace))
+			.collect::<Result<Vec<_>>>()
+			.map(|chunks| chunks.concat())
+	};
+	match pool {
+		Some(pool) => pool.install(work),
+		None => work(),
+	}
+}
+
+/// Records the computations performed over every block of a text, that is
+/// every column of the given `src_mtrx`, when multiplied by `key_mtrx`.
+fn trace_blocks(
+	key_mtrx: &ModMatrix,
+	src_mtrx: &ModMatrix,
+	namespace: &[char]
+) -> Vec<BlockTrace> {
+	let product = key_mtrx.unreduced_mul(src_mtrx);
+
+	(0..src_mtrx.cols())
+		.map(|col| {
+			let input_indices: Vec<_> = src_mtrx.col(col).collect();
+			let product: Vec<_> = product.iter().map(|row| row[col] as i64).collect();
+			let output_indices: Vec<_> = product
+				.iter()
+				.map(|&v| v as usize % namespace.len())
+				.collect();
+
+			BlockTrace {
+				input: input_indices.iter().map(|&i| namespace[i]).collect(),
+				output: output_indices.iter().map(|&i| namespace[i]).collect(),
+				input_indices,
+				product,
+				output_indices,
+			}
+		})
 		.collect()
 }
 
 /// Splits a given `text` into its numeric representations inside the namespace
-/// specified, and stores it inside a (Matrix)[rulinalg::matrix::Matrix] with
-/// `rows` x `cols` dimension.
+/// specified, and stores it inside a [`ModMatrix`] with `rows` x `cols`
+/// dimension.
 fn txt_mtrx_repr(
 	rows: usize,
 	cols: usize,
 	src: &str,
 	namespace: &[char]
-) -> Result<Matrix<f64>>
+) -> Result<ModMatrix>
 {
 	let parts: Vec<_> = src
 		.chars()
-		.map(|c| char_pos(c, namespace) as f64)
+		.map(|c| char_pos(c, namespace))
 		.collect();
 
-	Ok(Matrix::new(rows, cols, parts).transpose())
+	Ok(ModMatrix::new(rows, cols, parts, namespace.len()).transpose())
 }
 
 /// Fills a given `text` with a specified character (a - b) times.
@@ -318,7 +1403,7 @@ fn fill_txt(txt: &str, char: char, a: usize, b: usize) -> String {
 
 	if reps != 0 {
 		let append = char.to_string().repeat(reps);
-		format!("{}{}", txt, append).to_uppercase()
+		format!("{}{}", txt, append)
 	} else {
 		txt.to_owned()
 	}
@@ -326,29 +1411,12 @@ fn fill_txt(txt: &str, char: char, a: usize, b: usize) -> String {
 
 /// Retrives the given character's `position` inside the namespace specified.
 fn char_pos(char: char, namespace: &[char]) -> usize {
-	namespace.iter().position(|&c| c == char.to_ascii_uppercase()).unwrap()
+	namespace.iter().position(|&c| c == char).unwrap()
 }
 
-/// Checks if a `target number` has at least one factor against any number
-/// specified.
-fn has_any_factor(target: usize, number: usize) -> bool {
-	for factor in target..number {
-		if target % factor == 0 {
-			return true
-		}
-	}
-	false
-}
-
-/// Performs the modulus of a number in any other number specified,
-/// following the `Euclid` algorithm.
-fn euc_mod(a: i128, b: u128) -> u128 {
-    if a >= 0 {
-        (a as u128) % b
-    } else {
-        let r = (!a as u128) % b;
-        b - r - 1
-    }
+/// Computes the dimension of the square matrix the given `key` is turned into.
+pub fn key_dimension(key: &str) -> usize {
+	(key.chars().count() as f64).sqrt() as usize
 }
 
 /// Checks if the supplied number is square.
@@ -360,14 +1428,14 @@ fn is_square(num: usize) -> bool {
 
 /// Checks if the supplied target number is divisible by another one.
 fn is_divisble(target: usize, num: &usize) -> bool {
-	target % num == 0
+	target.is_multiple_of(*num)
 }
 
 /// Turns a given target number divisible by another one.
 fn turn_divisible(target: usize, dim: &usize) -> usize {
 	let mut base = target;
 	loop {
-		if base % dim == 0 {
+		if base.is_multiple_of(*dim) {
 			return base;
 		}
 		base += 1;
@@ -398,11 +1466,12 @@ mod tests {
 		let dim = (key.len() as f64).sqrt() as usize;
 
 		assert_eq!(
-			txt_mtrx_repr(dim, dim, &key, &DEFAULT_NAMESPACE).unwrap(),
-			Matrix::new(dim, dim,
-						vec![0.0, 3.0, 6.0,
-							 1.0, 4.0, 7.0,
-							 2.0, 5.0, 8.0]
+			txt_mtrx_repr(dim, dim, key, &DEFAULT_NAMESPACE).unwrap(),
+			ModMatrix::new(dim, dim,
+						vec![0, 3, 6,
+							 1, 4, 7,
+							 2, 5, 8],
+						26
 						 )
 		);
 	}
@@ -415,10 +1484,11 @@ mod tests {
 
 		assert_eq!(
 			txt_mtrx_repr(src.len() / dim, dim, &src, &DEFAULT_NAMESPACE).unwrap(),
-			Matrix::new(dim, src.len()/dim,
-						vec![2.0, 8.0,
-							 14.0, 6.0,
-							 3.0, 14.0]
+			ModMatrix::new(dim, src.len()/dim,
+						vec![2, 8,
+							 14, 6,
+							 3, 14],
+						26
 						 )
 		);
 	}
@@ -429,11 +1499,10 @@ mod tests {
 		let key = "FJCRXLUDN";
 		let src = "CODIGO".to_owned();
 		let dim = (key.len() as f64).sqrt() as usize;
-		let key_mtrx = txt_mtrx_repr(dim, dim, &key, &namespace).unwrap();
-		let src_mtrx = txt_mtrx_repr(src.len()/dim, dim, &src, &namespace).unwrap();
+		let key_mtrx = txt_mtrx_repr(dim, dim, key, &namespace).unwrap();
 
 		assert_eq!(
-			translate_txt_mtrx(&key_mtrx, src_mtrx, namespace),
+			translate_blocks(&key_mtrx, &src, &namespace).unwrap(),
 			String::from("WLPGSE")
 		);
 	}
@@ -444,30 +1513,207 @@ mod tests {
 		let key = "FJCRXLUDN";
 		let src = "WLPGSE".to_owned();
 		let dim = (key.len() as f64).sqrt() as usize;
-		let key_mtrx = txt_mtrx_repr(dim, dim, &key, &namespace).unwrap();
-		let src_mtrx = txt_mtrx_repr(src.len()/dim, dim, &src, &namespace).unwrap();
-
-		let key_mtrx_inv = key_mtrx.clone().inverse().unwrap();
-		let key_mtrx_det =  key_mtrx.det();
-		let mod_mul_inv = modinverse::modinverse(
-			key_mtrx_det as i64,
-			namespace.len() as i64
-		).unwrap() as f64;
-
-		let key_mtrx_inv = Matrix::new(
-			key_mtrx_inv.rows(),
-			key_mtrx_inv.cols(),
-			key_mtrx_inv
-				.into_vec()
-				.into_iter()
-				.map(|v| ((v * mod_mul_inv) * key_mtrx_det).round())
-				.collect::<Vec<_>>()
-		);
+		let key_mtrx = txt_mtrx_repr(dim, dim, key, &namespace).unwrap();
+		let key_mtrx_inv = key_mtrx.inverse().unwrap();
 
 		assert_eq!(
-			translate_txt_mtrx(&key_mtrx_inv, src_mtrx, namespace),
+			translate_blocks(&key_mtrx_inv, &src, &namespace).unwrap(),
 			String::from("CODIGO")
 		);
+		assert!(matches!(
+			translate_blocks(&key_mtrx_inv, "WLPGS", &namespace),
+			Err(Error::PartialBlock { len: 5, dimension: 3 })
+		));
+	}
+
+	#[test]
+	#[cfg(feature = "rayon")]
+	fn blocks_processed_in_parallel_keep_their_order() {
+		let namespace = DEFAULT_NAMESPACE.to_vec();
+		let key_mtrx = txt_mtrx_repr(3, 3, "FJCRXLUDN", &namespace).unwrap();
+		let src: String = namespace.iter().cycle().take(3 * 100_000).collect();
+
+		assert_eq!(
+			translate_parallel(&key_mtrx, &src, &namespace, thread_pool(4).as_ref()).unwrap(),
+			translate_blocks(&key_mtrx, &src, &namespace).unwrap()
+		);
+	}
+
+	#[test]
+	fn report_is_rendered_in_compact_and_verbose_styles() {
+		let report = ReportBuilder::default()
+			.used_key("FJCRXLUDN".to_owned())
+			.source_txt("CODIGOX".to_owned())
+			.result_txt("WLPGSEKZG".to_owned())
+			.fill_letter(Some('H'))
+			.filled(true)
+			.def_namespace(None)
+			.build()
+			.unwrap();
+
+		assert_eq!(
+			report.render(ReportStyle::Compact),
+			"Result text: WLPGSEKZG\nFilled?: true"
+		);
+		assert!(report.to_string().starts_with(
+			"Used key: FJCRXLUDN\nSource text: CODIGOX\nResult text: WLPGSEKZG\n"
+		));
+		assert!(report.to_string().contains("Namespace: Default namespace\n"));
+	}
+
+	#[test]
+	fn report_round_trips_through_json() {
+		let report = ReportBuilder::default()
+			.used_key("FJCRXLUDN".to_owned())
+			.source_txt("CODIGO".to_owned())
+			.result_txt("WLPGSE".to_owned())
+			.fill_letter(Some('H'))
+			.filled(false)
+			.def_namespace(None)
+			.key_dimension(3)
+			.block_count(2)
+			.build()
+			.unwrap();
+
+		let json = report.to_json().unwrap();
+		assert!(json.contains("\"result_txt\": \"WLPGSE\""));
+		assert_eq!(serde_json::from_str::<Report>(&json).unwrap(), report);
+	}
+
+	#[test]
+	fn block_computations_are_traced() {
+		let report = ProcessorBuilder::default()
+			.key("FJCRXLUDN".to_owned())
+			.source("CODIGO".to_owned())
+			.fill_letter(None)
+			.namespace(None)
+			.trace_blocks(true)
+			.build()
+			.unwrap()
+			.cipher()
+			.unwrap();
+
+		assert_eq!(
+			report.blocks[0],
+			BlockTrace {
+				input: "COD".to_owned(),
+				input_indices: vec![2, 14, 3],
+				product: vec![308, 349, 197],
+				output_indices: vec![22, 11, 15],
+				output: "WLP".to_owned(),
+			}
+		);
+		assert_eq!(report.blocks[1].output, "GSE");
+		assert_eq!(report.block_table().lines().nth(2), Some("| COD         | 2 14 3        | 22 11 15      | WLP         |"));
+	}
+
+	#[test]
+	fn key_matrices_are_built_out_of_keys_of_any_script() {
+		let processor = ProcessorBuilder::default()
+			.key("\u{c9}BCD".to_owned())
+			.source(String::new())
+			.fill_letter(None)
+			.namespace(Some("ABCDEFGHIJKLMNOPQRSTUVWXYZ\u{c9}".to_owned()))
+			.build()
+			.unwrap();
+
+		assert_eq!(processor.key_matrix().unwrap().transpose().into_vec(), vec![26, 1, 2, 3]);
+	}
+
+	#[test]
+	fn max_key_dimension_shrinks_with_the_namespace() {
+		assert_eq!(max_key_dimension(DEFAULT_NAMESPACE.len()), 8);
+		assert_eq!(max_key_dimension(36), 7);
+		assert!(max_key_dimension(1000) < max_key_dimension(36));
+	}
+
+	#[test]
+	fn bad_blocks_are_replaced_by_placeholders() {
+		let report = ProcessorBuilder::default()
+			.key("FJCRXLUDN".to_owned())
+			.source("CODI1OCOD".to_owned())
+			.fill_letter(None)
+			.namespace(None)
+			.bad_blocks(BadBlocks::Placeholder('?'))
+			.build()
+			.unwrap()
+			.cipher()
+			.unwrap();
+
+		assert_eq!(report.result_txt, "WLP???WLP");
+		assert_eq!(report.source_txt, "CODI1OCOD");
+		assert_eq!(report.failed_blocks.len(), 1);
+		assert_eq!(report.failed_blocks[0].index, 1);
+	}
+
+	#[test]
+	fn texts_are_compared() {
+		let comparison = Comparison::new("WLPGSE", "WLPGXEA");
+
+		assert_eq!(
+			comparison,
+			Comparison { first_difference: Some(4), matching: 5, length: 7 }
+		);
+		assert_eq!(Comparison::new("WLP", "WLP").first_difference, None);
+	}
+
+	/// Checks the ciphertexts against the ones of reference implementations,
+	/// listed in `tests/fixtures/interop.tsv` and in any other fixtures file
+	/// given through `HILL_CIPHER_INTEROP_FIXTURES`, separated as in `PATH`.
+	#[test]
+	#[ignore = "interop suite, run with `cargo test -- --ignored`"]
+	fn ciphertexts_match_reference_implementations() {
+		let mut paths = vec![
+			std::path::PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/interop.tsv"))
+		];
+		if let Some(extra) = std::env::var_os("HILL_CIPHER_INTEROP_FIXTURES") {
+			paths.extend(std::env::split_paths(&extra));
+		}
+
+		for path in paths {
+			let fixtures = std::fs::read_to_string(&path).unwrap();
+			let rows = fixtures
+				.lines()
+				.filter(|line| !line.trim().is_empty() && !line.starts_with('#'));
+
+			for row in rows {
+				let fields: Vec<_> = row.split('\t').collect();
+				let [reference, convention, key, namespace, plaintext, ciphertext] = fields[..] else {
+					panic!("malformed fixture in {}: {row}", path.display());
+				};
+
+				// `column` keys are laid out transposed to the way they are read here
+				let key = match convention {
+					"row" => key.to_owned(),
+					"column" => {
+						let key: Vec<_> = key.chars().collect();
+						let dim = (key.len() as f64).sqrt() as usize;
+						(0..key.len()).map(|i| key[(i % dim) * dim + i / dim]).collect()
+					},
+					_ => panic!("unknown convention '{convention}' in {}", path.display()),
+				};
+				let processor = |source: &str| ProcessorBuilder::default()
+					.key(key.clone())
+					.source(source.to_owned())
+					.fill_letter(Some('X'))
+					.namespace((namespace != "-").then(|| namespace.to_owned()))
+					.build()
+					.unwrap();
+
+				let ciphered = processor(plaintext).cipher().unwrap();
+				assert_eq!(ciphered.result_txt, ciphertext, "ciphering as {reference}: {row}");
+				let deciphered = processor(ciphertext).decipher().unwrap();
+				assert!(
+					deciphered.result_txt.starts_with(plaintext),
+					"deciphering as {reference}: {row}"
+				);
+			}
+		}
+	}
+
+	/// Clears the timestamps of a report, since they differ between runs.
+	fn untimed(report: Report) -> Report {
+		Report { started_at: String::new(), finished_at: String::new(), ..report }
 	}
 
 	#[derive(Clone)]
@@ -503,10 +1749,12 @@ mod tests {
 			.fill_letter(info.fill_letter)
 			.filled(false)
 			.def_namespace(info.namespace)
+			.key_dimension(3)
+			.block_count(2)
 			.build()
 			.unwrap();
 
-		assert_eq!(processor.cipher().unwrap(), report);
+		assert_eq!(untimed(processor.cipher().unwrap()), report);
 	}
 
 	#[test]
@@ -534,10 +1782,12 @@ mod tests {
 			.fill_letter(info.fill_letter)
 			.filled(false)
 			.def_namespace(info.namespace)
+			.key_dimension(3)
+			.block_count(2)
 			.build()
 			.unwrap();
 
-		assert_eq!(processor.decipher().unwrap(), report);
+		assert_eq!(untimed(processor.decipher().unwrap()), report);
 	}
 
 	#[test]
@@ -565,11 +1815,14 @@ mod tests {
 			.result_txt("XR$HNK^BJQ@?".to_owned())
 			.fill_letter(info.fill_letter)
 			.filled(true)
+			.padding(vec![11])
 			.def_namespace(info.namespace)
+			.key_dimension(4)
+			.block_count(3)
 			.build()
 			.unwrap();
 
-		assert_eq!(processor.cipher().unwrap(), report);
+		assert_eq!(untimed(processor.cipher().unwrap()), report);
 	}
 
 	#[test]
@@ -598,9 +1851,293 @@ mod tests {
 			.fill_letter(info.fill_letter)
 			.filled(false)
 			.def_namespace(info.namespace)
+			.key_dimension(4)
+			.block_count(3)
+			.build()
+			.unwrap();
+
+		assert_eq!(untimed(processor.decipher().unwrap()), report);
+	}
+
+	#[test]
+	fn blocks_are_processed_lazily() {
+		let processor = ProcessorBuilder::default()
+			.key("FJCRXLUDN".to_owned())
+			.source("CODIGOC".to_owned())
+			.fill_letter(Some('X'))
+			.namespace(None)
+			.build()
+			.unwrap();
+
+		let ciphered = processor.cipher_blocks("CODIGOC".chars()).collect::<Result<String>>().unwrap();
+		assert_eq!(ciphered, processor.cipher().unwrap().result_txt);
+
+		let processor = ProcessorBuilder::default()
+			.key("FJCRXLUDN".to_owned())
+			.source(String::new())
+			.fill_letter(None)
+			.namespace(None)
+			.build()
+			.unwrap();
+		let mut blocks = processor.decipher_blocks("ABC!EF".chars());
+		assert_eq!(blocks.by_ref().take(3).count(), 3);
+		assert!(matches!(blocks.next(), Some(Err(Error::UnknownChar('!', None)))));
+		assert!(blocks.next().is_none());
+	}
+
+	#[test]
+	fn processor_is_shareable_across_threads() {
+		fn assert_send_sync<T: Send + Sync>() {}
+		assert_send_sync::<Processor>();
+		assert_send_sync::<Report>();
+
+		let processor = std::sync::Arc::new(ProcessorBuilder::default()
+			.key("FJCRXLUDN".to_owned())
+			.source(String::new())
+			.fill_letter(Some('X'))
+			.namespace(None)
+			.build()
+			.unwrap());
+
+		let handles: Vec<_> = ["CODIGO", "CODIGOC", "HILL"]
+			.into_iter()
+			.map(|text| {
+				let processor = std::sync::Arc::clone(&processor);
+				std::thread::spawn(move || processor.cipher_blocks(text.chars()).collect::<Result<String>>())
+			})
+			.collect();
+		let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap().unwrap()).collect();
+
+		assert_eq!(results[0], results[1][..6]);
+		assert_eq!(results[2].len(), 6);
+	}
+
+	#[test]
+	fn keys_over_the_maximum_dimension_are_rejected() {
+		let processor = |max| ProcessorBuilder::default()
+			.key(format!("B{}", "A".repeat(80)))
+			.source("ABC".to_owned())
+			.fill_letter(Some('X'))
+			.namespace(None)
+			.max_key_dimension(max)
+			.build()
+			.unwrap();
+
+		assert!(matches!(processor(Some(8)).cipher(), Err(Error::KeyTooLarge { dimension: 9, max: 8 })));
+		assert!(!matches!(processor(Some(9)).cipher(), Err(Error::KeyTooLarge { .. })));
+		assert!(!matches!(processor(None).cipher(), Err(Error::KeyTooLarge { .. })));
+	}
+
+	#[test]
+	fn large_keys_round_trip_with_exact_arithmetic() {
+		let key = "BRVFDSSUGLKPFPAAQTBMECRFLJPMCAUDJSMUZQKTRGNHXCVNMFBHXEHVPMRBNACLBDMXBVNOIRGPNVSXSVJFKJXXCYRHOPUKVMPC";
+		let processor = |source: String| ProcessorBuilder::default()
+			.key(key.to_owned())
+			.source(source)
+			.fill_letter(Some('X'))
+			.namespace(None)
+			.build()
+			.unwrap();
+
+		let ciphered = processor("THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG".to_owned()).cipher().unwrap();
+		let deciphered = processor(ciphered.result_txt).decipher().unwrap();
+		assert_eq!(deciphered.result_txt, "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOGXXXXX");
+	}
+
+	#[test]
+	fn edge_case_inputs_have_defined_results() {
+		let processor = |key: &str, source: &str, fill_letter, namespace: Option<&str>| {
+			ProcessorBuilder::default()
+				.key(key.to_owned())
+				.source(source.to_owned())
+				.fill_letter(fill_letter)
+				.namespace(namespace.map(str::to_owned))
+				.build()
+				.unwrap()
+		};
+
+		// an empty source text gives an empty result
+		let report = processor("GYBNQKURP", "", None, None).cipher().unwrap();
+		assert_eq!((report.result_txt.as_str(), report.block_count), ("", 0));
+		assert_eq!(processor("GYBNQKURP", "", None, None).decipher().unwrap().result_txt, "");
+
+		// a one-character key multiplies every character by itself
+		assert_eq!(processor("D", "HELLO", None, None).cipher().unwrap().result_txt, "VMHHQ");
+
+		assert!(matches!(processor("", "AB", Some('X'), None).cipher(), Err(Error::EmptyKey)));
+		assert!(matches!(processor("A", "A", Some('A'), Some("A")).cipher(), Err(Error::NamespaceTooShort)));
+		assert!(matches!(processor("GYBNQKURP", "AB", None, None).cipher(), Err(Error::MissingFillLetter)));
+
+		// a source text made only of the fill letter is ciphered as any other
+		let report = processor("GYBNQKURP", "XXXX", Some('X'), None).cipher().unwrap();
+		assert!(report.filled);
+		let report = processor("GYBNQKURP", &report.result_txt, Some('X'), None).decipher().unwrap();
+		assert_eq!(report.result_txt, "XXXXXX");
+	}
+
+	#[test]
+	fn iterations_apply_the_key_repeatedly() {
+		let cipher = |source: &str, iterations| ProcessorBuilder::default()
+			.key("GYBNQKURP".to_owned())
+			.source(source.to_owned())
+			.fill_letter(Some('X'))
+			.namespace(None)
+			.iterations(iterations)
+			.build()
+			.unwrap()
+			.cipher()
+			.unwrap();
+
+		let once = cipher("ACTTHEREWEGO", 1);
+		assert_eq!(once.effective_key, None);
+		let twice = cipher(&once.result_txt, 1);
+		let report = cipher("ACTTHEREWEGO", 2);
+		assert_eq!(report.result_txt, twice.result_txt);
+
+		let effective = ProcessorBuilder::default()
+			.key(report.effective_key.unwrap())
+			.source("ACTTHEREWEGO".to_owned())
+			.fill_letter(Some('X'))
+			.namespace(None)
+			.build()
+			.unwrap();
+		assert_eq!(effective.cipher().unwrap().result_txt, twice.result_txt);
+	}
+
+	#[test]
+	fn texts_are_transcoded_between_alphabets() {
+		let digits: Vec<_> = "0123456789".chars().collect();
+		let letters = &DEFAULT_NAMESPACE[..10];
+
+		let transcoded = transcode("BAD JOB", letters, &digits);
+		assert_eq!(transcoded, "103 9O1");
+		assert_eq!(transcode(&transcoded, &digits, letters), "BAD JOB");
+	}
+
+	#[test]
+	fn texts_are_normalized_before_processing() {
+		assert_eq!(Normalization::Nfc.apply("CAFE\u{301}"), "CAF\u{c9}");
+		assert_eq!(Normalization::Nfd.apply("CAF\u{c9}"), "CAFE\u{301}");
+		assert_eq!(Normalization::None.apply("CAFE\u{301}"), "CAFE\u{301}");
+		// the accent may still combine with a letter read later
+		assert_eq!(Normalization::Nfc.stable_len("CAFE\u{301}"), 3);
+		assert_eq!(Normalization::None.stable_len("CAFE\u{301}"), 6);
+	}
+
+	#[test]
+	fn characters_are_folded_into_the_namespace() {
+		let namespace: HashSet<_> = "ABCÇIİẞaz".chars().collect();
+
+		// the characters of the namespace are kept whatever their case
+		assert_eq!(CaseFolding::Upper.apply("abiıßz", &namespace), "aBIIẞz");
+		assert_eq!(CaseFolding::Turkish.apply("abiıßz", &namespace), "aBİIẞz");
+		assert_eq!(CaseFolding::None.apply("abiıßz", &namespace), "abiıßz");
+	}
+
+	#[test]
+	fn namespaces_are_built_out_of_the_texts() {
+		assert_eq!(auto_namespace(None, &["HILL", "ÑANDÚ"]), "ADHILNÑÚ");
+		assert_eq!(auto_namespace(None, &["BA", "CE", "D"]), "ABCDE");
+		assert_eq!(auto_namespace(Some("AB"), &["BAD"]), "ABD");
+		assert_eq!(auto_namespace(None, &["AAA"]), "A ");
+	}
+
+	#[test]
+	fn missing_characters_get_suggestions() {
+		use crate::error::Suggestion;
+
+		assert_eq!(suggest('a', &DEFAULT_NAMESPACE), Some(Suggestion::Case('A')));
+		assert_eq!(suggest('é', &DEFAULT_NAMESPACE), Some(Suggestion::Unaccented('E')));
+		assert_eq!(suggest('\u{301}', &DEFAULT_NAMESPACE), Some(Suggestion::Normalize));
+		assert_eq!(suggest('!', &DEFAULT_NAMESPACE), None);
+	}
+
+	#[test]
+	fn invalid_keys_are_explained() {
+		let lines = explain(&Error::InvalidKeyMatrix(26), "abcdefghi", None);
+		assert_eq!(lines[0], "the key's matrix has a determinant of 0 modulo 26");
+		assert!(lines[2].ends_with("1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25"));
+		assert_eq!(lines.len(), 3);
+		assert!(explain(&Error::InvalidKeyMatrix(26), "AAAA", None)[3].contains("keyspace enumerate"));
+		assert!(explain(&Error::EmptyKey, "", None).is_empty());
+	}
+
+	#[test]
+	fn batches_count_their_items() {
+		let report = |filled| ReportBuilder::default()
+			.used_key("GYBNQKURP".into())
+			.source_txt("ACT".into())
+			.fill_letter(None)
+			.result_txt("POH".into())
+			.filled(filled)
+			.def_namespace(None)
+			.build()
+			.unwrap();
+		let batch = Batch::new(vec![
+			("ACT".into(), Ok(report(false))),
+			("AC".into(), Ok(report(true))),
+			("".into(), Err(Error::EmptyKey)),
+		]);
+
+		assert_eq!(batch.summary, BatchSummary { processed: 3, failed: 1, filled: 1 });
+		assert!(batch.items[2].report.is_none() && batch.items[2].error.is_some());
+		assert!(batch.render().starts_with("  1. ACT -> POH\n  2. AC -> POH\n  3.  -> "));
+	}
+
+	#[test]
+	fn namespaces_of_any_length_are_accepted() {
+		let spanish = "ABCDEFGHIJKLMNÑOPQRSTUVWXYZ";
+		let processor = |source: &str| ProcessorBuilder::default()
+			.key("GYBNQKURP".to_owned())
+			.source(source.to_owned())
+			.fill_letter(Some('X'))
+			.namespace(Some(spanish.to_owned()))
+			.build()
+			.unwrap();
+
+		let ciphered = processor("MAÑANA").cipher().unwrap();
+		assert_eq!(processor(&ciphered.result_txt).decipher().unwrap().result_txt, "MAÑANA");
+	}
+
+	#[test]
+	fn processors_with_other_sources_share_the_key_setup() {
+		let base = ProcessorBuilder::default()
+			.key("GYBNQKURP".to_owned())
+			.source(String::new())
+			.fill_letter(Some('X'))
+			.namespace(None)
+			.build()
+			.unwrap();
+
+		assert_eq!(base.with_source("ACT".to_owned()).cipher().unwrap().result_txt, "QRT");
+		assert!(base.setup.cipher.get().is_some() && base.setup.decipher.get().is_none());
+		assert_eq!(base.with_source("HELLO".to_owned()).cipher().unwrap().result_txt, "CDEGVC");
+		assert_eq!(base.with_source("QRT".to_owned()).decipher().unwrap().result_txt, "ACT");
+	}
+
+	#[test]
+	fn key_matrices_are_wiped() {
+		use zeroize::Zeroize as _;
+
+		let mut key = txt_mtrx_repr(3, 3, "GYBNQKURP", &DEFAULT_NAMESPACE).unwrap();
+		key.zeroize();
+		assert_eq!(key, ModMatrix::new(3, 3, vec![0; 9], DEFAULT_NAMESPACE.len()));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn persisted_processors_are_replayed() {
+		let processor = ProcessorBuilder::default()
+			.key("GYBNQKURP".to_owned())
+			.source("ACT".to_owned())
+			.fill_letter(None)
+			.namespace(None)
+			.bad_blocks(BadBlocks::Placeholder('?'))
 			.build()
 			.unwrap();
+		let job = serde_json::to_string(&processor).unwrap();
 
-		assert_eq!(processor.decipher().unwrap(), report);
+		let replayed: Processor = serde_json::from_str(&job).unwrap();
+		assert_eq!(replayed.cipher().unwrap().result_txt, "QRT");
 	}
 }
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use tracing::Subscriber;
+use tracing_subscriber::Layer;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::error::Result;
+
+/// Evaluates the given `body` inside a span named after a stage of the
+/// processes, so the time spent in it is recorded by the profiler. Without the
+/// `profiling` feature, the body is evaluated as it is.
+#[macro_export]
+macro_rules! stage {
+	($name:literal, $body:expr) => {{
+		#[cfg(feature = "profiling")]
+		let _stage = tracing::info_span!($name).entered();
+		$body
+	}};
+}
+
+/// Guard writing the recorded samples out once dropped.
+#[cfg(feature = "profiling")]
+pub type Guard = tracing_flame::FlushGuard<std::io::BufWriter<std::fs::File>>;
+
+#[cfg(not(feature = "profiling"))]
+pub type Guard = ();
+
+/// Builds the layer recording the time spent in every stage as folded stack
+/// samples into the file at `path`, which `inferno-flamegraph` renders as a
+/// flamegraph.
+#[cfg(feature = "profiling")]
+pub fn layer<S>(path: &Path) -> Result<(impl Layer<S>, Guard)>
+where
+	S: Subscriber + for<'a> LookupSpan<'a>,
+{
+	tracing_flame::FlameLayer::with_file(path)
+		.map_err(|e| format!("the profile file '{}' cannot be created: {e}", path.display()).into())
+}
+
+#[cfg(not(feature = "profiling"))]
+pub fn layer<S>(_: &Path) -> Result<(impl Layer<S>, Guard)>
+where
+	S: Subscriber + for<'a> LookupSpan<'a>,
+{
+	Err::<(tracing_subscriber::layer::Identity, _), _>(
+		"this build cannot profile, it must be built with the `profiling` feature".into()
+	)
+}
@@ -0,0 +1,50 @@
+use std::io::{self, IsTerminal as _};
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// Size, in bytes, of the files from which their processing shows a progress
+/// bar.
+pub const BYTES_THRESHOLD: u64 = 4 * 1024 * 1024;
+
+/// Number of texts of the batches from which their processing shows a
+/// progress bar.
+pub const ITEMS_THRESHOLD: u64 = 100;
+
+/// Makes a progress bar on stderr over the `total` bytes of a file, with its
+/// throughput and the time left. It is hidden if `quiet`, if the file is
+/// smaller than [`BYTES_THRESHOLD`] or if stderr is not a terminal.
+pub fn bytes(total: u64, quiet: bool) -> ProgressBar {
+	bar(
+		total,
+		quiet || total < BYTES_THRESHOLD,
+		"{bar:40.cyan/blue} {bytes}/{total_bytes} ({binary_bytes_per_sec}, {eta} left)"
+	)
+}
+
+/// Makes a progress bar on stderr over the `total` texts of a batch, with its
+/// throughput and the time left. It is hidden if `quiet`, if the batch has
+/// fewer texts than [`ITEMS_THRESHOLD`] or if stderr is not a terminal.
+pub fn items(total: u64, quiet: bool) -> ProgressBar {
+	bar(
+		total,
+		quiet || total < ITEMS_THRESHOLD,
+		"{bar:40.cyan/blue} {pos}/{len} ({per_sec}, {eta} left)"
+	)
+}
+
+fn bar(total: u64, hidden: bool, template: &str) -> ProgressBar {
+	if hidden || !io::stderr().is_terminal() {
+		return ProgressBar::hidden();
+	}
+
+	// the bar is only styled along with the rest of the output
+	let template = if colored::control::SHOULD_COLORIZE.should_colorize() {
+		template.to_owned()
+	} else {
+		template.replace(".cyan/blue", "")
+	};
+	let style = ProgressStyle::with_template(&template)
+		.expect("the progress bar templates are valid")
+		.progress_chars("=> ");
+	ProgressBar::with_draw_target(Some(total), ProgressDrawTarget::stderr()).with_style(style)
+}
@@ -0,0 +1,76 @@
+use std::path::Path;
+
+use image::Luma;
+use qrcode::{QrCode, render::{svg, unicode::Dense1x2}};
+use rqrr::PreparedImage;
+
+use crate::error::Result;
+
+/// Encodes the given `payload` as a QR code.
+fn encode(payload: &str) -> Result<QrCode> {
+	QrCode::new(payload.as_bytes())
+		.map_err(|e| format!("the text cannot be encoded as a QR code: {e}").into())
+}
+
+/// Prints the given `payload` as a QR code made of unicode half blocks. The
+/// colors are inverted so the code is readable on dark terminals.
+pub fn print(payload: &str) -> Result<()> {
+	let code = encode(payload)?;
+	eprintln!(
+		"{}",
+		code.render::<Dense1x2>()
+			.dark_color(Dense1x2::Light)
+			.light_color(Dense1x2::Dark)
+			.build()
+	);
+
+	Ok(())
+}
+
+/// Saves the given `payload` as a QR code image to `path`. The image format
+/// is picked by the file extension, which must be either `png` or `svg`.
+pub fn save(payload: &str, path: &Path) -> Result<()> {
+	let code = encode(payload)?;
+	let extension = path.extension()
+		.and_then(|e| e.to_str())
+		.map(str::to_ascii_lowercase);
+
+	match extension.as_deref() {
+		Some("png") => code.render::<Luma<u8>>()
+			.min_dimensions(256, 256)
+			.build()
+			.save(path)
+			.map_err(|e| e.to_string())?,
+		Some("svg") => std::fs::write(
+			path,
+			code.render::<svg::Color>().min_dimensions(256, 256).build()
+		).map_err(|e| e.to_string())?,
+		_ => return Err(
+			format!("cannot save the QR code to '{}', use a .png or .svg file", path.display()).into()
+		),
+	}
+
+	Ok(())
+}
+
+/// Reads the payload of the first QR code found inside the image at `path`,
+/// such as a `.png` file or a `.jpg` photo of a code.
+pub fn read(path: &Path) -> Result<String> {
+	let image = image::open(path)
+		.map_err(|e| format!("cannot open the image '{}': {e}", path.display()))?
+		.to_luma8();
+
+	let mut prepared = PreparedImage::prepare_from_greyscale(
+		image.width() as usize,
+		image.height() as usize,
+		|x, y| image.get_pixel(x as u32, y as u32).0[0]
+	);
+	let grids = prepared.detect_grids();
+	let grid = grids.first()
+		.ok_or(format!("no QR code was found inside '{}'", path.display()))?;
+
+	let (_, payload) = grid.decode()
+		.map_err(|e| format!("the QR code inside '{}' cannot be decoded: {e}", path.display()))?;
+
+	Ok(payload.trim_end_matches(['\n', '\r']).to_owned())
+}
@@ -0,0 +1,114 @@
+use colored::Colorize as _;
+use rustyline::{DefaultEditor, error::ReadlineError};
+
+use crate::error::Result;
+use crate::process::{ProcessorBuilder, Report, namespace_from};
+use crate::ui;
+
+const HELP: &str = "\
+  c <text>        cipher the given text
+  d <text>        decipher the given text
+  key <key>       set the key to use
+  fill <letter>   set the fill letter (empty to unset)
+  ns <namespace>  set the namespace, either a preset name or a custom one
+  show            show the loaded settings
+  help            show this message
+  quit            leave the repl";
+
+/// Settings kept loaded between the commands of a repl session.
+struct Session {
+	key: Option<String>,
+	fill_letter: Option<char>,
+	namespace: Option<String>,
+}
+
+impl Session {
+	/// Ciphers or deciphers the given `text` with the loaded settings.
+	fn process(&self, text: &str, ciphering: bool) -> Result<Report> {
+		let key = self.key.clone()
+			.ok_or("no key is loaded yet, use `key <key>` first")?;
+		let processor = ProcessorBuilder::default()
+			.key(key)
+			.source(text.to_owned())
+			.fill_letter(self.fill_letter)
+			.namespace(self.namespace.clone())
+			.build()
+			.unwrap();
+
+		if ciphering {
+			processor.cipher()
+		} else {
+			processor.decipher()
+		}
+	}
+
+	/// Runs a single repl `line`, returning `false` if the session must end.
+	fn eval(&mut self, line: &str) -> Result<bool> {
+		let (cmd, arg) = match line.split_once(' ') {
+			Some((cmd, arg)) => (cmd, arg),
+			None => (line, ""),
+		};
+
+		match cmd {
+			"c" | "cipher" | "d" | "decipher" => {
+				let report = self.process(arg, cmd.starts_with('c'))?;
+				eprintln!(
+					"{} {}{}",
+					"=>".blue().bold(),
+					report.result_txt,
+					if report.filled { " (filled)".yellow() } else { "".normal() }
+				);
+			},
+			"key" => self.key = Some(arg.to_owned()).filter(|k| !k.is_empty()),
+			"fill" => {
+				let mut chars = arg.chars();
+				self.fill_letter = match (chars.next(), chars.next()) {
+					(fill, None) => fill,
+					_ => return Err("the fill letter must be a single character".into()),
+				};
+			},
+			"ns" | "namespace" => self.namespace = namespace_from(arg),
+			"show" => eprintln!(
+				"  {}: {}\n  {}: {}\n  {}: {}",
+				"Key".yellow(), self.key.as_deref().unwrap_or("-"),
+				"Fill letter".yellow(), self.fill_letter.map_or("-".to_owned(), String::from),
+				"Namespace".yellow(), self.namespace.as_deref().unwrap_or("Default namespace"),
+			),
+			"help" | "?" => eprintln!("{HELP}"),
+			"quit" | "exit" | "q" => return Ok(false),
+			_ => return Err(format!("unknown command `{cmd}`, try `help`").into()),
+		}
+
+		Ok(true)
+	}
+}
+
+/// Runs an interactive session that keeps the key, fill letter and namespace
+/// loaded between the `cipher` and `decipher` commands typed by the user.
+pub fn run(key: Option<String>, fill_letter: Option<char>, namespace: Option<String>) -> Result<()> {
+	let mut editor = DefaultEditor::new().map_err(|e| e.to_string())?;
+	let mut session = Session { key, fill_letter, namespace };
+
+	eprintln!("{} (type `help` for the available commands)", "Hill cipher repl".green().bold());
+	loop {
+		let line = match editor.readline("hill> ") {
+			Ok(line) => line,
+			Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+			Err(e) => return Err(e.to_string().into()),
+		};
+
+		let line = line.trim_start();
+		if line.is_empty() {
+			continue;
+		}
+		editor.add_history_entry(line).ok();
+
+		match session.eval(line) {
+			Ok(true) => (),
+			Ok(false) => break,
+			Err(e) => ui::print_error(e),
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use rand::SeedableRng as _;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom as _;
+
+use crate::error::Result;
+use crate::process::DEFAULT_NAMESPACE;
+
+/// English text the pseudo-English samples are learned from.
+pub const CORPUS: &str = "It was the best of times, it was the worst of times, it was the \
+	age of wisdom, it was the age of foolishness, it was the epoch of belief, it was \
+	the epoch of incredulity, it was the season of light, it was the season of \
+	darkness, it was the spring of hope, it was the winter of despair. We had \
+	everything before us, we had nothing before us, we were all going direct to \
+	heaven, we were all going direct the other way. There were a king with a large \
+	jaw and a queen with a plain face on the throne of England; there were a king \
+	with a large jaw and a queen with a fair face on the throne of France. In both \
+	countries it was clearer than crystal to the lords of the state preserves of \
+	loaves and fishes, that things in general were settled for ever.";
+
+/// Creates the random generator of the samples, seeded with the given `seed`
+/// for reproducible outputs, or from the operating system otherwise.
+pub fn rng(seed: Option<u64>) -> StdRng {
+	match seed {
+		Some(seed) => StdRng::seed_from_u64(seed),
+		None => StdRng::from_os_rng(),
+	}
+}
+
+/// Generates a text of `length` characters picked uniformly from the given
+/// `namespace`, or from the default one if none is given.
+pub fn uniform(length: usize, namespace: Option<&str>, rng: &mut StdRng) -> Result<String> {
+	let namespace = namespace_chars(namespace);
+	if namespace.is_empty() {
+		return Err("the namespace to sample from is empty".into());
+	}
+
+	Ok((0..length).map(|_| *namespace.choose(rng).unwrap()).collect())
+}
+
+/// Generates a pseudo-English text of `length` characters by sampling which
+/// character follows the previous one as they do in english text. Only the
+/// characters of the given `namespace`, or the default one, are produced.
+pub fn english(length: usize, namespace: Option<&str>, rng: &mut StdRng) -> Result<String> {
+	let namespace = namespace_chars(namespace);
+	let corpus: Vec<_> = CORPUS
+		.to_uppercase()
+		.chars()
+		.filter(|c| namespace.contains(c))
+		.collect();
+	if corpus.len() < 2 {
+		return Err("the namespace shares no characters with english text".into());
+	}
+
+	// every character is followed by one of the characters that follow it in
+	// the corpus
+	let mut successors: HashMap<char, Vec<char>> = HashMap::new();
+	for pair in corpus.windows(2) {
+		successors.entry(pair[0]).or_default().push(pair[1]);
+	}
+
+	let mut text = String::with_capacity(length);
+	let mut current = *corpus.choose(rng).unwrap();
+	for _ in 0..length {
+		text.push(current);
+		current = *successors
+			.get(&current)
+			.and_then(|next| next.choose(rng))
+			.unwrap_or_else(|| corpus.choose(rng).unwrap());
+	}
+
+	Ok(text)
+}
+
+/// Collects the characters of the given `namespace`, or of the default one.
+fn namespace_chars(namespace: Option<&str>) -> Vec<char> {
+	match namespace {
+		Some(namespace) => namespace.chars().collect(),
+		None => DEFAULT_NAMESPACE.to_vec(),
+	}
+}
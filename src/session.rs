@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::error::Result;
+use crate::input::{Format, KeyOpts};
+use crate::output;
+
+/// Settings of the last cipher or decipher, remembered so that the next one
+/// can reuse them with `--last`. Only references to the key are remembered,
+/// never the key itself.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+	/// `pass:<entry>` reference to the key
+	pub key: Option<String>,
+	/// Image holding the key as a QR code
+	pub key_qr: Option<PathBuf>,
+	/// File holding the key
+	pub key_file: Option<PathBuf>,
+	/// Environment variable holding the key
+	pub key_env: Option<String>,
+	pub fill_letter: Option<char>,
+	pub namespace: Option<String>,
+	/// Name of the report's format
+	pub format: Option<String>,
+}
+
+impl Session {
+	/// Remembers the given settings. A key given as it is is left out.
+	pub fn new(key: &KeyOpts, fill_letter: Option<char>, namespace: Option<&str>, format: Format) -> Self {
+		if key.key.as_deref().is_some_and(|key| !is_reference(key)) {
+			warn!("the key is not remembered in the session, only references to it like pass:<entry> are");
+		}
+
+		Session {
+			key: key.key.clone().filter(|key| is_reference(key)),
+			key_qr: key.key_qr.clone(),
+			key_file: key.key_file.clone(),
+			key_env: key.key_env.clone(),
+			fill_letter,
+			namespace: namespace.map(str::to_owned),
+			format: Some(format.name().to_owned()),
+		}
+	}
+
+	/// Loads the session saved at `path`.
+	pub fn load(path: &Path) -> Result<Self> {
+		let state = fs::read_to_string(path).map_err(|e| session_error(path, &e))?;
+		serde_json::from_str(&state)
+			.map_err(|e| format!("the session '{}' is malformed: {e}", path.display()).into())
+	}
+
+	/// Records the session at `path`, readable only by its owner and never
+	/// left half written.
+	pub fn save(&self, path: &Path) -> Result<()> {
+		let state = serde_json::to_string(self).map_err(|e| e.to_string())?;
+		output::write_atomic(path, state.as_bytes(), output::private_permissions())
+			.map_err(|e| session_error(path, &e))
+	}
+
+	/// Fills the settings that were not given with the ones of the session.
+	/// The format is only taken from the session if the default one is given.
+	pub fn apply(
+		self,
+		key: &mut KeyOpts,
+		fill_letter: &mut Option<char>,
+		namespace: &mut Option<String>,
+		format: &mut Format
+	) -> Result<()> {
+		// a key written by an older version as it is is not trusted
+		if !key.is_given() {
+			key.key = self.key.filter(|key| is_reference(key));
+			key.key_qr = self.key_qr;
+			key.key_file = self.key_file;
+			key.key_env = self.key_env;
+		}
+		*fill_letter = fill_letter.or(self.fill_letter);
+		if namespace.is_none() {
+			*namespace = self.namespace;
+		}
+		if let (Format::Text, Some(name)) = (*format, self.format) {
+			*format = name.parse()?;
+		}
+		debug!(key_given = key.is_given(), "last session settings reused");
+
+		Ok(())
+	}
+}
+
+/// Whether the given `key` refers to one kept elsewhere rather than being the
+/// key itself.
+fn is_reference(key: &str) -> bool {
+	key.starts_with("pass:")
+}
+
+/// Builds the error returned when a session cannot be read or written.
+fn session_error(path: &Path, err: &std::io::Error) -> crate::error::Error {
+	format!("the session '{}' cannot be accessed: {err}", path.display()).into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn last_session_fills_the_settings_not_given() {
+		let given = KeyOpts { key: Some("pass:hill".to_owned()), ..Default::default() };
+		let session = Session::new(&given, Some('X'), Some("ABCD"), Format::Json);
+
+		let (mut key, mut fill_letter, mut namespace, mut format) = (KeyOpts::default(), Some('Q'), None, Format::Text);
+		session.apply(&mut key, &mut fill_letter, &mut namespace, &mut format).unwrap();
+		assert_eq!(key.key.as_deref(), Some("pass:hill"));
+		assert_eq!((fill_letter, namespace.as_deref(), format), (Some('Q'), Some("ABCD"), Format::Json));
+
+		// the key itself is never remembered
+		let given = KeyOpts { key: Some("GYBNQKURP".to_owned()), ..Default::default() };
+		assert_eq!(Session::new(&given, None, None, Format::Text).key, None);
+	}
+}
@@ -0,0 +1,454 @@
+use std::fs;
+use std::io::{BufRead as _, BufReader, Read};
+use std::path::Path;
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use flate2::read::GzDecoder;
+use memmap2::Mmap;
+use tracing::debug;
+
+use crate::error::Result;
+use crate::input::{EncodingChoice, NewlineMode, SourceOpts};
+
+/// Reads the source text from the file at `path`, decoding it as told by
+/// [`decode`]. Gzip files are decompressed on the fly.
+pub fn read(path: &Path, encoding: EncodingChoice, keep_bom: bool) -> Result<String> {
+	let bytes = fs::read(path)
+		.map_err(|e| format!("the source file '{}' cannot be read: {e}", path.display()))?;
+	let bytes = if is_gzip(path, &bytes) {
+		debug!("decompressing the gzip source file");
+		gunzip(&bytes)
+			.map_err(|e| format!("the source file '{}' cannot be decompressed: {e}", path.display()))?
+	} else {
+		bytes
+	};
+
+	decode(&bytes, encoding, keep_bom).map_err(|encoding| {
+		format!("the source file '{}' is not valid {}", path.display(), encoding.name()).into()
+	})
+}
+
+/// Reads the source text from stdin until it is closed, decoding it as told by
+/// [`decode`].
+pub fn read_stdin(encoding: EncodingChoice, keep_bom: bool) -> Result<String> {
+	let mut bytes = Vec::new();
+	std::io::stdin()
+		.read_to_end(&mut bytes)
+		.map_err(|e| format!("the source text cannot be read from stdin: {e}"))?;
+
+	decode(&bytes, encoding, keep_bom).map_err(|encoding| {
+		format!("the source text from stdin is not valid {}", encoding.name()).into()
+	})
+}
+
+/// Fetches the source text from the body of the given HTTP(S) `url`, decoding
+/// it as told by [`decode`].
+#[cfg(feature = "url")]
+pub fn fetch(url: &str, encoding: EncodingChoice, keep_bom: bool) -> Result<String> {
+	debug!(url, "fetching the source text");
+	let bytes = reqwest::blocking::get(url)
+		.and_then(|response| response.error_for_status())
+		.and_then(|response| response.bytes())
+		.map_err(|e| format!("the source URL '{url}' cannot be fetched: {e}"))?;
+
+	decode(&bytes, encoding, keep_bom).map_err(|encoding| {
+		format!("the body of '{url}' is not valid {}", encoding.name()).into()
+	})
+}
+
+#[cfg(not(feature = "url"))]
+pub fn fetch(_: &str, _: EncodingChoice, _: bool) -> Result<String> {
+	Err("this build cannot fetch URLs, it must be built with the `url` feature".into())
+}
+
+/// Whether the file at `path` is a stream, like a named pipe, a socket or a
+/// character device, that must be read as its data arrives.
+pub fn is_stream(path: &Path) -> bool {
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::FileTypeExt as _;
+
+		fs::metadata(path).is_ok_and(|meta| {
+			let kind = meta.file_type();
+			kind.is_fifo() || kind.is_socket() || kind.is_char_device()
+		})
+	}
+	#[cfg(not(unix))]
+	{
+		let _ = path;
+		false
+	}
+}
+
+/// Size from which the plain source files that are streamed are memory-mapped
+/// and their chunks decoded right from the map, rather than read.
+pub const MAP_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+/// Largest number of bytes read from a stream at once. Reading a file fills
+/// the whole of it, so its chunks are large enough for their blocks to be
+/// processed in parallel, while a pipe returns whatever data has arrived.
+const READ_SIZE: usize = 2 * 1024 * 1024;
+
+/// Tells whether the file at `path` is a plain, not compressed file of over
+/// `max_size` bytes, too large to be read whole but fit to be streamed.
+pub fn is_oversized(path: &Path, max_size: u64) -> bool {
+	if !fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.len() > max_size) {
+		return false;
+	}
+
+	let mut magic = [0; 2];
+	let read = fs::File::open(path).and_then(|mut file| file.read(&mut magic)).unwrap_or_default();
+	!is_gzip(path, &magic[..read])
+}
+
+/// Reads the source text from the stream at `path` as its data arrives,
+/// decoding and normalizing it as told by the `opts`, and hands it to `on_chunk` in chunks made
+/// of whole blocks of `dimension` characters, so they can be processed right
+/// away, for as long as it returns `true`. The last chunk holds whatever
+/// remains, and may need filling. Since the data cannot be sniffed beforehand,
+/// an automatic encoding only honors a byte order mark and falls back to UTF-8.
+/// Gzip streams are decompressed as they are read, and files of
+/// [`MAP_THRESHOLD`] bytes or more are memory-mapped.
+pub fn stream(
+	path: &Path,
+	opts: &SourceOpts,
+	dimension: usize,
+	mut on_chunk: impl FnMut(&str) -> Result<bool>
+) -> Result<()> {
+	let read_error = |e: std::io::Error| format!("the source stream '{}' cannot be read: {e}", path.display());
+	let file = fs::File::open(path).map_err(read_error)?;
+	let map = match file.metadata() {
+		Ok(meta) if meta.is_file() && meta.len() >= MAP_THRESHOLD => Some(map(&file).map_err(read_error)?),
+		_ => None,
+	};
+	let reader: Box<dyn Read> = match &map {
+		Some(map) => Box::new(&map[..]),
+		None => Box::new(file),
+	};
+	// the magic bytes are peeked at, since a stream cannot be read twice
+	let mut reader = BufReader::new(reader);
+	let mut reader: Box<dyn Read> = if is_gzip(path, reader.fill_buf().map_err(read_error)?) {
+		debug!("decompressing the gzip source stream");
+		Box::new(GzDecoder::new(reader))
+	} else {
+		Box::new(reader)
+	};
+
+	let encoding = match opts.encoding {
+		EncodingChoice::Auto => UTF_8,
+		EncodingChoice::Label(encoding) => encoding,
+	};
+	let mut decoder = if opts.keep_bom {
+		encoding.new_decoder_without_bom_handling()
+	} else {
+		encoding.new_decoder()
+	};
+	let is_char = |c: char| opts.newlines == NewlineMode::Namespace || !['\r', '\n'].contains(&c);
+
+	let mut buffer = vec![0; READ_SIZE];
+	let mut decoded = String::new();
+	let mut pending = String::new();
+	loop {
+		let read = reader.read(&mut buffer).map_err(read_error)?;
+		let last = read == 0;
+		decoded.reserve(decoder.max_utf8_buffer_length(read).unwrap_or(read));
+		let (_, _, malformed) = decoder.decode_to_string(&buffer[..read], &mut decoded, last);
+		if malformed {
+			return Err(format!("the source stream '{}' is malformed", path.display()).into());
+		}
+
+		// the blocks are counted once normalized, as they are processed, out of
+		// the text nothing read later can combine with
+		let stable = if last { decoded.len() } else { opts.normalize.stable_len(&decoded) };
+		pending.push_str(&opts.normalize.apply(&decoded[..stable]));
+		decoded.drain(..stable);
+		if last {
+			break;
+		}
+
+		// cutting right after the last character completing a block
+		let mut count = 0usize;
+		let mut cut = 0;
+		for (i, c) in pending.char_indices().filter(|&(_, c)| is_char(c)) {
+			count += 1;
+			if count.is_multiple_of(dimension.max(1)) {
+				cut = i + c.len_utf8();
+			}
+		}
+		if cut > 0 {
+			debug!(bytes = cut, "processing a chunk of the source stream");
+			if !on_chunk(&pending[..cut])? {
+				return Ok(());
+			}
+			pending.drain(..cut);
+		}
+	}
+
+	if !pending.is_empty() {
+		on_chunk(&pending)?;
+	}
+	Ok(())
+}
+
+/// Memory-maps the given plain `file`, to be read once from its start to its
+/// end.
+fn map(file: &fs::File) -> std::io::Result<Mmap> {
+	// SAFETY: the map is only read, and a file truncated meanwhile is the same
+	// hazard as one truncated while it is read
+	let map = unsafe { Mmap::map(file)? };
+	#[cfg(unix)]
+	map.advise(memmap2::Advice::Sequential)?;
+	debug!(bytes = map.len(), "memory mapped the source file");
+	Ok(map)
+}
+
+/// Reads only a slice of the source text from the file at `path`, made of
+/// `length` blocks of `dimension` characters starting at the block `offset`,
+/// or of every block from there if no length is given. The file is decoded as
+/// it is read, as told by the `opts`, and its reading stops once the slice is
+/// complete.
+pub fn read_blocks(
+	path: &Path,
+	opts: &SourceOpts,
+	dimension: usize,
+	offset: usize,
+	length: Option<usize>
+) -> Result<String> {
+	let mut slicer = Slicer::new(opts.newlines, dimension, offset, length);
+	stream(path, opts, dimension, |chunk| Ok(slicer.push(chunk)))?;
+	Ok(slicer.text)
+}
+
+/// Takes a slice of whole blocks out of a text given in chunks.
+pub struct Slicer {
+	newlines: NewlineMode,
+	start: usize,
+	end: Option<usize>,
+	seen: usize,
+	/// Characters of the slice taken so far
+	pub text: String,
+}
+
+impl Slicer {
+	/// Creates a slicer taking `length` blocks of `dimension` characters from
+	/// the block `offset` on, or every block from there if no length is given.
+	/// Line terminators only count as characters with
+	/// [`NewlineMode::Namespace`].
+	pub fn new(newlines: NewlineMode, dimension: usize, offset: usize, length: Option<usize>) -> Self {
+		let start = offset * dimension;
+		Slicer {
+			newlines,
+			start,
+			end: length.map(|length| start + length * dimension),
+			seen: 0,
+			text: String::new(),
+		}
+	}
+
+	/// Takes the characters of the slice out of the given `chunk`, returning
+	/// whether more characters are needed to complete it.
+	pub fn push(&mut self, chunk: &str) -> bool {
+		for c in chunk.chars() {
+			let inside = self.end.is_none_or(|end| self.seen < end);
+			if self.newlines == NewlineMode::Namespace || !['\r', '\n'].contains(&c) {
+				if self.seen >= self.start && inside {
+					self.text.push(c);
+				}
+				self.seen += 1;
+			} else if self.seen >= self.start && inside {
+				self.text.push(c);
+			}
+		}
+
+		self.end.is_none_or(|end| self.seen < end)
+	}
+}
+
+/// Decodes the given `bytes` with the given `encoding`, or with the one
+/// detected from them. A byte order mark is dropped unless it is asked to
+/// `keep_bom`, in which case it remains as the leading `U+FEFF` character. If
+/// the bytes are malformed, the encoding they were decoded with is returned.
+pub fn decode(
+	bytes: &[u8],
+	encoding: EncodingChoice,
+	keep_bom: bool
+) -> std::result::Result<String, &'static Encoding> {
+	let encoding = match encoding {
+		EncodingChoice::Auto => detect(bytes),
+		EncodingChoice::Label(encoding) => encoding,
+	};
+	debug!(encoding = encoding.name(), keep_bom, "decoding the source text");
+
+	let (text, malformed) = if keep_bom {
+		encoding.decode_without_bom_handling(bytes)
+	} else {
+		encoding.decode_with_bom_removal(bytes)
+	};
+	if malformed {
+		return Err(encoding);
+	}
+
+	Ok(text.into_owned())
+}
+
+/// Decompresses the given gzip compressed `bytes`.
+pub fn gunzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+	let mut decompressed = Vec::new();
+	GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+	Ok(decompressed)
+}
+
+/// Whether the file at `path` is gzip compressed, either by its extension or
+/// by its magic bytes.
+pub fn is_gzip(path: &Path, bytes: &[u8]) -> bool {
+	path.extension().is_some_and(|ext| ext == "gz") || bytes.starts_with(&[0x1f, 0x8b])
+}
+
+/// Line terminators taken out of a text, along with the positions they were
+/// found at and the length of the text without them.
+#[derive(Debug, Default)]
+pub struct LineBreaks {
+	breaks: Vec<(usize, &'static str)>,
+	text_len: usize,
+}
+
+/// Takes the line terminators out of the given `text`, according to the given
+/// `mode`, so they are not processed. With [`NewlineMode::Namespace`], the
+/// text is left as is.
+pub fn split_lines(text: &str, mode: NewlineMode) -> (String, LineBreaks) {
+	if mode == NewlineMode::Namespace {
+		return (text.to_owned(), LineBreaks::default());
+	}
+
+	let mut breaks = Vec::new();
+	let mut stripped = String::with_capacity(text.len());
+	let mut len = 0;
+	let mut chars = text.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'\r' if chars.peek() == Some(&'\n') => {
+				chars.next();
+				breaks.push((len, "\r\n"));
+			},
+			'\n' => breaks.push((len, "\n")),
+			c => {
+				stripped.push(c);
+				len += 1;
+			},
+		}
+	}
+
+	(stripped, LineBreaks { breaks, text_len: len })
+}
+
+/// Puts the line `breaks` taken out of a source text back into the `result`
+/// text, converting them according to the given `mode`. The breaks found at
+/// the end of the source text remain at the end of the result, after any
+/// characters added by filling.
+pub fn restore_lines(result: &str, breaks: &LineBreaks, mode: NewlineMode) -> String {
+	if breaks.breaks.is_empty() {
+		return result.to_owned();
+	}
+
+	let len = result.chars().count();
+	let at = |pos: usize, i: usize| if pos < breaks.text_len { pos == i } else { i == len };
+	let mut breaks = breaks.breaks.iter().peekable();
+	let mut restored = String::with_capacity(result.len() + 2 * breaks.len());
+	for (i, c) in result.chars().chain(std::iter::once('\0')).enumerate() {
+		while let Some((_, terminator)) = breaks.next_if(|(pos, _)| at(*pos, i)) {
+			restored.push_str(match mode {
+				NewlineMode::Lf => "\n",
+				NewlineMode::Crlf => "\r\n",
+				_ => terminator,
+			});
+		}
+		if i < len {
+			restored.push(c);
+		}
+	}
+
+	restored
+}
+
+/// Guesses the encoding of the given `bytes`: a byte order mark is trusted
+/// first, then text with many zero bytes is taken as UTF-16, then valid UTF-8
+/// is taken as such, and anything else as Latin-1.
+fn detect(bytes: &[u8]) -> &'static Encoding {
+	if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+		return encoding;
+	}
+
+	// the zero bytes of mostly ASCII UTF-16 text sit at odd positions for the
+	// little endian variant and at even ones for the big endian one
+	let zeros = |parity: usize| {
+		bytes.iter().skip(parity).step_by(2).filter(|&&b| b == 0).count()
+	};
+	let even_len = bytes.len().is_multiple_of(2);
+	let half = bytes.len() / 4;
+	if even_len && zeros(1) > half {
+		UTF_16LE
+	} else if even_len && zeros(0) > half {
+		UTF_16BE
+	} else if std::str::from_utf8(bytes).is_ok() {
+		UTF_8
+	} else {
+		WINDOWS_1252
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::process::ProcessorBuilder;
+
+	#[test]
+	fn streamed_decomposed_texts_are_processed_as_whole_ones() {
+		// long enough to be read twice, the first read ending inside the
+		// accent of a letter
+		let text = "E\u{301}AB".repeat(500_000);
+		let path = std::env::temp_dir().join(format!("hill_cypher_stream_{}.txt", std::process::id()));
+		fs::write(&path, &text).unwrap();
+		let processor = ProcessorBuilder::default()
+			.key("FJCRXLUDN".to_owned())
+			.source(String::new())
+			.fill_letter(Some('X'))
+			.namespace(Some("ABCDEFGHIJKLMNOPQRSTUVWXYZ\u{c9}".to_owned()))
+			.build()
+			.unwrap();
+
+		let mut streamed = String::new();
+		let outcome = stream(&path, &SourceOpts::default(), 3, |chunk| {
+			streamed.push_str(&processor.with_source(chunk.to_owned()).cipher()?.result_txt);
+			Ok(true)
+		});
+		fs::remove_file(&path).unwrap();
+		outcome.unwrap();
+
+		let whole = processor.with_source(text).cipher().unwrap().result_txt;
+		assert_eq!(streamed.chars().count(), 1_500_000);
+		assert_eq!(streamed, whole);
+	}
+
+	#[test]
+	fn gzip_streams_are_decompressed() {
+		use std::io::Write as _;
+
+		let text = "HELLOWORLD".repeat(2000);
+		let path = std::env::temp_dir().join(format!("hill_cypher_stream_{}.txt.gz", std::process::id()));
+		let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+		encoder.write_all(text.as_bytes()).unwrap();
+		fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+		let mut streamed = String::new();
+		let outcome = stream(&path, &SourceOpts::default(), 3, |chunk| {
+			streamed.push_str(chunk);
+			Ok(true)
+		});
+		let sliced = read_blocks(&path, &SourceOpts::default(), 2, 1, Some(2));
+		fs::remove_file(&path).unwrap();
+		outcome.unwrap();
+
+		assert_eq!(streamed, text);
+		assert_eq!(sliced.unwrap(), "LLOW");
+	}
+}
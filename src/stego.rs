@@ -0,0 +1,57 @@
+use crate::error::Result;
+
+/// Zero width character standing for a 0 bit.
+const ZERO: char = '\u{200B}';
+/// Zero width character standing for a 1 bit.
+const ONE: char = '\u{200C}';
+
+/// Hides the given `payload` inside the `cover` text, as zero width
+/// characters right after the cover's first character, eight per byte of the
+/// payload. The cover text reads the same as before.
+pub fn hide(payload: &str, cover: &str) -> Result<String> {
+	if cover.contains([ZERO, ONE]) {
+		return Err("the cover text already holds hidden characters".into());
+	}
+
+	let hidden: String = payload
+		.bytes()
+		.flat_map(|byte| (0..8).rev().map(move |bit| if byte >> bit & 1 == 1 { ONE } else { ZERO }))
+		.collect();
+	let split = cover.chars().next().map_or(0, char::len_utf8);
+
+	Ok(format!("{}{hidden}{}", &cover[..split], &cover[split..]))
+}
+
+/// Extracts the payload hidden by [`hide`] inside the given `text`.
+pub fn extract(text: &str) -> Result<String> {
+	let bits: Vec<u8> = text
+		.chars()
+		.filter_map(|c| match c {
+			ZERO => Some(0),
+			ONE => Some(1),
+			_ => None,
+		})
+		.collect();
+	if bits.is_empty() {
+		return Err("the text holds no hidden characters".into());
+	}
+	if !bits.len().is_multiple_of(8) {
+		return Err("the hidden characters of the text are incomplete".into());
+	}
+
+	let bytes = bits.chunks(8).map(|byte| byte.iter().fold(0, |acc, bit| acc << 1 | bit)).collect();
+	String::from_utf8(bytes).map_err(|_| "the hidden characters of the text are not valid UTF-8".into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn payloads_hide_inside_cover_texts() {
+		let hidden = hide("QRTZ\nMTWO", "Dear Ana, all is well.").unwrap();
+		assert_eq!(hidden.chars().filter(char::is_ascii).collect::<String>(), "Dear Ana, all is well.");
+		assert_eq!(extract(&hidden).unwrap(), "QRTZ\nMTWO");
+		assert!(extract("Dear Ana").is_err());
+	}
+}
@@ -0,0 +1,380 @@
+use std::io::{self, Read, Write};
+
+use crate::error::Result;
+use crate::process::{Normalization, Processor, ProcessorBuilder, key_dimension};
+
+#[cfg(feature = "async")]
+pub use self::tokio_adapters::{AsyncCipherReader, AsyncCipherWriter};
+
+/// Operation performed over the data flowing through a stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+	Cipher,
+	Decipher,
+}
+
+/// Transforms UTF-8 text given in arbitrary pieces, processing it as soon as
+/// whole blocks of the key's dimension are available, and keeping the partial
+/// ones until more data arrives.
+#[derive(Debug)]
+pub struct Engine {
+	mode: Mode,
+	/// Processor of the engine's settings, whose key is set up once for every
+	/// block
+	processor: Processor,
+	dimension: usize,
+	/// Bytes of a character split across pieces
+	bytes: Vec<u8>,
+	/// Characters that may still combine with the next ones, not normalized
+	/// yet
+	raw: String,
+	/// Normalized characters of a partial block
+	text: String,
+}
+
+impl Engine {
+	/// Creates an engine performing the given `mode` with the given settings,
+	/// as a [`Processor`](crate::process::Processor) would. The key is wiped
+	/// from memory once the engine is dropped.
+	pub fn new(mode: Mode, key: String, fill_letter: Option<char>, namespace: Option<String>) -> Self {
+		Engine {
+			mode,
+			dimension: key_dimension(&key).max(1),
+			processor: ProcessorBuilder::default()
+				.key(key)
+				.source(String::new())
+				.fill_letter(fill_letter)
+				.namespace(namespace)
+				.build()
+				.unwrap(),
+			bytes: Vec::new(),
+			raw: String::new(),
+			text: String::new(),
+		}
+	}
+
+	/// Takes the next piece of data, returning the result of the whole blocks
+	/// completed by it.
+	pub fn push(&mut self, bytes: &[u8]) -> Result<String> {
+		self.bytes.extend_from_slice(bytes);
+		let valid = match std::str::from_utf8(&self.bytes) {
+			Ok(text) => text.len(),
+			// a character may be split across pieces
+			Err(e) if e.error_len().is_none() => e.valid_up_to(),
+			Err(_) => return Err("the streamed data is not valid UTF-8".into()),
+		};
+		self.raw.push_str(std::str::from_utf8(&self.bytes[..valid]).unwrap());
+		self.bytes.drain(..valid);
+
+		// the blocks are counted once normalized, as the processes do
+		let form = Normalization::default();
+		let stable = form.stable_len(&self.raw);
+		self.text.push_str(&form.apply(&self.raw[..stable]));
+		self.raw.drain(..stable);
+
+		let whole = self.text.chars().count() / self.dimension * self.dimension;
+		let cut = self.text.char_indices().nth(whole).map_or(self.text.len(), |(i, _)| i);
+		if cut == 0 {
+			return Ok(String::new());
+		}
+		let blocks: String = self.text.drain(..cut).collect();
+		self.process(blocks)
+	}
+
+	/// Completes the data, returning the result of the last partial block,
+	/// which is filled when ciphering.
+	pub fn finish(&mut self) -> Result<String> {
+		if !self.bytes.is_empty() {
+			return Err("the streamed data ends in the middle of a character".into());
+		}
+
+		let raw = std::mem::take(&mut self.raw);
+		self.text.push_str(&Normalization::default().apply(&raw));
+		match std::mem::take(&mut self.text) {
+			rest if rest.is_empty() => Ok(rest),
+			rest => self.process(rest),
+		}
+	}
+
+	/// Processes the given `text` with the engine's settings.
+	fn process(&self, text: String) -> Result<String> {
+		let processor = self.processor.with_source(text);
+		let report = match self.mode {
+			Mode::Cipher => processor.cipher()?,
+			Mode::Decipher => processor.decipher()?,
+		};
+
+		Ok(report.result_txt)
+	}
+}
+
+/// Transforms the data written to an inner [`Write`] with an [`Engine`],
+/// buffering the partial blocks. The last partial block is only written by
+/// [`CipherWriter::finish`].
+pub struct CipherWriter<W: Write> {
+	inner: W,
+	engine: Engine,
+}
+
+impl<W: Write> CipherWriter<W> {
+	/// Wraps the given `inner` writer, transforming the data written to it
+	/// with the given `engine`.
+	pub fn new(inner: W, engine: Engine) -> Self {
+		CipherWriter { inner, engine }
+	}
+
+	/// Writes the result of the last partial block, flushing and returning the
+	/// inner writer.
+	pub fn finish(mut self) -> io::Result<W> {
+		let result = self.engine.finish().map_err(io_error)?;
+		self.inner.write_all(result.as_bytes())?;
+		self.inner.flush()?;
+		Ok(self.inner)
+	}
+}
+
+impl<W: Write> Write for CipherWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let result = self.engine.push(buf).map_err(io_error)?;
+		self.inner.write_all(result.as_bytes())?;
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+/// Transforms the data read from an inner [`Read`] with an [`Engine`],
+/// buffering the partial blocks until the inner reader ends.
+pub struct DecipherReader<R: Read> {
+	inner: R,
+	engine: Engine,
+	/// Results not read yet
+	out: Vec<u8>,
+	done: bool,
+}
+
+impl<R: Read> DecipherReader<R> {
+	/// Wraps the given `inner` reader, transforming its data with the given
+	/// `engine`.
+	pub fn new(inner: R, engine: Engine) -> Self {
+		DecipherReader { inner, engine, out: Vec::new(), done: false }
+	}
+
+	/// Unwraps the inner reader.
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+
+impl<R: Read> Read for DecipherReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		// reading until there are results to hand or the inner reader ends
+		while self.out.is_empty() && !self.done {
+			let mut chunk = [0; 8192];
+			let read = self.inner.read(&mut chunk)?;
+			let result = if read == 0 {
+				self.done = true;
+				self.engine.finish()
+			} else {
+				self.engine.push(&chunk[..read])
+			};
+			self.out.extend_from_slice(result.map_err(io_error)?.as_bytes());
+		}
+
+		let n = self.out.len().min(buf.len());
+		buf[..n].copy_from_slice(&self.out[..n]);
+		self.out.drain(..n);
+		Ok(n)
+	}
+}
+
+/// Turns an error of the engine into an I/O one.
+fn io_error(err: crate::error::Error) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, crate::i18n::tr_error(&err))
+}
+
+#[cfg(feature = "async")]
+mod tokio_adapters {
+	use std::io;
+	use std::pin::Pin;
+	use std::task::{Context, Poll, ready};
+
+	use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+	use super::{Engine, io_error};
+
+	/// Transforms the data read from an inner [`AsyncRead`] with an [`Engine`].
+	pub struct AsyncCipherReader<R> {
+		inner: R,
+		engine: Engine,
+		/// Results not read yet
+		out: Vec<u8>,
+		done: bool,
+	}
+
+	impl<R: AsyncRead + Unpin> AsyncCipherReader<R> {
+		/// Wraps the given `inner` reader, transforming its data with the
+		/// given `engine`.
+		pub fn new(inner: R, engine: Engine) -> Self {
+			AsyncCipherReader { inner, engine, out: Vec::new(), done: false }
+		}
+
+		/// Unwraps the inner reader.
+		pub fn into_inner(self) -> R {
+			self.inner
+		}
+	}
+
+	impl<R: AsyncRead + Unpin> AsyncRead for AsyncCipherReader<R> {
+		fn poll_read(
+			self: Pin<&mut Self>,
+			cx: &mut Context<'_>,
+			buf: &mut ReadBuf<'_>
+		) -> Poll<io::Result<()>> {
+			let this = self.get_mut();
+
+			// reading until there are results to hand or the inner reader ends
+			while this.out.is_empty() && !this.done {
+				let mut chunk = [0; 8192];
+				let mut chunk = ReadBuf::new(&mut chunk);
+				ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk))?;
+
+				let result = if chunk.filled().is_empty() {
+					this.done = true;
+					this.engine.finish()
+				} else {
+					this.engine.push(chunk.filled())
+				};
+				this.out.extend_from_slice(result.map_err(io_error)?.as_bytes());
+			}
+
+			let n = this.out.len().min(buf.remaining());
+			buf.put_slice(&this.out[..n]);
+			this.out.drain(..n);
+			Poll::Ready(Ok(()))
+		}
+	}
+
+	/// Transforms the data written to an inner [`AsyncWrite`] with an
+	/// [`Engine`]. The last partial block is only written on shutdown.
+	pub struct AsyncCipherWriter<W> {
+		inner: W,
+		engine: Engine,
+		/// Results not written yet
+		out: Vec<u8>,
+		finished: bool,
+	}
+
+	impl<W: AsyncWrite + Unpin> AsyncCipherWriter<W> {
+		/// Wraps the given `inner` writer, transforming the data written to it
+		/// with the given `engine`.
+		pub fn new(inner: W, engine: Engine) -> Self {
+			AsyncCipherWriter { inner, engine, out: Vec::new(), finished: false }
+		}
+
+		/// Unwraps the inner writer, dropping any result not written yet.
+		pub fn into_inner(self) -> W {
+			self.inner
+		}
+
+		/// Writes the pending results to the inner writer.
+		fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+			while !self.out.is_empty() {
+				let n = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.out))?;
+				if n == 0 {
+					return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+				}
+				self.out.drain(..n);
+			}
+			Poll::Ready(Ok(()))
+		}
+	}
+
+	impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncCipherWriter<W> {
+		fn poll_write(
+			self: Pin<&mut Self>,
+			cx: &mut Context<'_>,
+			buf: &[u8]
+		) -> Poll<io::Result<usize>> {
+			let this = self.get_mut();
+			ready!(this.poll_drain(cx))?;
+
+			let result = this.engine.push(buf).map_err(io_error)?;
+			this.out.extend_from_slice(result.as_bytes());
+			Poll::Ready(Ok(buf.len()))
+		}
+
+		fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+			let this = self.get_mut();
+			ready!(this.poll_drain(cx))?;
+			Pin::new(&mut this.inner).poll_flush(cx)
+		}
+
+		fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+			let this = self.get_mut();
+			if !this.finished {
+				let result = this.engine.finish().map_err(io_error)?;
+				this.out.extend_from_slice(result.as_bytes());
+				this.finished = true;
+			}
+			ready!(this.poll_drain(cx))?;
+			Pin::new(&mut this.inner).poll_shutdown(cx)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn data_round_trips_through_the_stream_adapters() {
+		let engine = |mode| Engine::new(mode, "FJCRXLUDN".to_owned(), Some('X'), None);
+		let mut writer = CipherWriter::new(Vec::new(), engine(Mode::Cipher));
+		for piece in ["CO", "DIG", "OC"] {
+			writer.write_all(piece.as_bytes()).unwrap();
+		}
+		let ciphered = writer.finish().unwrap();
+
+		let mut deciphered = String::new();
+		DecipherReader::new(&ciphered[..], engine(Mode::Decipher))
+			.read_to_string(&mut deciphered)
+			.unwrap();
+		assert_eq!(deciphered, "CODIGOCXX");
+	}
+
+	#[cfg(feature = "async")]
+	#[tokio::test]
+	async fn data_round_trips_through_the_async_adapters() {
+		use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+		let namespace = Some("ABCDEFGHIJKLMNOPQRSTUVWXYZ\u{c9}".to_owned());
+		let engine = |mode| Engine::new(mode, "FJCRXLUDN".to_owned(), Some('X'), namespace.clone());
+
+		// the pieces split the blocks, and one of them a character
+		let mut writer = AsyncCipherWriter::new(Vec::new(), engine(Mode::Cipher));
+		for piece in [&b"C\xc3"[..], b"\x89DIG", b"OC"] {
+			writer.write_all(piece).await.unwrap();
+		}
+		writer.shutdown().await.unwrap();
+		let ciphered = writer.into_inner();
+		assert_eq!(String::from_utf8(ciphered.clone()).unwrap().chars().count(), 9);
+
+		// the ciphertext arrives a few bytes at a time
+		let (mut sender, receiver) = tokio::io::duplex(4);
+		let mut reader = AsyncCipherReader::new(receiver, engine(Mode::Decipher));
+		let mut deciphered = String::new();
+		let (sent, read) = tokio::join!(
+			async {
+				sender.write_all(&ciphered).await?;
+				sender.shutdown().await
+			},
+			reader.read_to_string(&mut deciphered)
+		);
+		sent.unwrap();
+		read.unwrap();
+		assert_eq!(deciphered, "C\u{c9}DIGOCXX");
+	}
+}
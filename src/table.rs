@@ -0,0 +1,78 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use tracing::debug;
+
+use crate::error::{Error, Result};
+use crate::{i18n, interrupt};
+
+/// Guesses the field delimiter of the table file at `path` by its extension:
+/// a tab for `.tsv` files and a comma otherwise.
+pub fn delimiter_of(path: &Path) -> u8 {
+	if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("tsv")) { b'\t' } else { b',' }
+}
+
+/// Processes the texts of the given `column` of the table read from `reader`,
+/// writing its rows to `writer` with their results appended as a new
+/// `result_column`. Empty cells are left empty. The number of processed rows
+/// is retrieved; a row that cannot be processed stops the whole table, and so
+/// does an interruption.
+pub fn transform(
+	reader: impl Read,
+	writer: impl Write,
+	delimiter: u8,
+	column: &str,
+	result_column: &str,
+	mut process: impl FnMut(&str) -> Result<String>
+) -> Result<usize> {
+	let mut reader = csv::ReaderBuilder::new().delimiter(delimiter).from_reader(reader);
+	let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(writer);
+
+	let mut headers = reader.headers().map_err(|e| format!("the table cannot be read: {e}"))?.clone();
+	let index = headers
+		.iter()
+		.position(|header| header == column)
+		.ok_or_else(|| format!("the table has no '{column}' column"))?;
+	headers.push_field(result_column);
+	writer.write_record(&headers).map_err(|e| e.to_string())?;
+
+	let mut processed = 0;
+	for (row, record) in reader.records().enumerate() {
+		if interrupt::interrupted() {
+			return Err(Error::Interrupted);
+		}
+		// the header is the first line, and rows are counted from 1
+		let mut record = record.map_err(|e| format!("the row {} cannot be read: {e}", row + 1))?;
+		let text = record.get(index).unwrap_or_default();
+		let result = if text.is_empty() {
+			String::new()
+		} else {
+			processed += 1;
+			process(text).map_err(|e| format!("the row {} cannot be processed: {}", row + 1, i18n::tr_error(&e)))?
+		};
+		record.push_field(&result);
+		writer.write_record(&record).map_err(|e| e.to_string())?;
+	}
+	debug!(processed, "table processed");
+
+	writer.flush().map_err(|e| e.to_string())?;
+	Ok(processed)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn table_columns_are_processed() {
+		let input = "id,message\n1,ACT\n2,\n";
+		let mut output = Vec::new();
+		let processed = transform(input.as_bytes(), &mut output, b',', "message", "result", |text| {
+			Ok(text.to_lowercase())
+		}).unwrap();
+
+		assert_eq!(processed, 1);
+		assert_eq!(String::from_utf8(output).unwrap(), "id,message,result\n1,ACT,act\n2,,\n");
+		assert!(transform(input.as_bytes(), Vec::new(), b',', "text", "result", |t| Ok(t.into())).is_err());
+	}
+}
@@ -0,0 +1,191 @@
+use ratatui::{
+	DefaultTerminal, Frame,
+	crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+	layout::{Constraint, Layout, Rect},
+	style::{Color, Modifier, Style},
+	text::{Line, Span},
+	widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::error::Result;
+use crate::i18n;
+use crate::process::{ProcessorBuilder, Report, namespace_from};
+
+/// Labels of the fields editable inside the interface.
+const FIELDS: [&str; 4] = ["Key", "Fill letter", "Namespace", "Source text"];
+
+/// State of the interface: the fields' contents, the focused field and the
+/// operation to preview.
+#[derive(Default)]
+struct App {
+	fields: [String; 4],
+	focus: usize,
+	deciphering: bool,
+}
+
+impl App {
+	/// Builds a processor with the current fields' contents.
+	fn processor(&self) -> Result<ProcessorBuilder> {
+		let [key, fill, namespace, source] = &self.fields;
+
+		let mut chars = fill.chars();
+		let fill_letter = match (chars.next(), chars.next()) {
+			(fill, None) => fill,
+			_ => return Err("the fill letter must be a single character".into()),
+		};
+
+		let mut builder = ProcessorBuilder::default();
+		builder
+			.key(key.clone())
+			.source(source.clone())
+			.fill_letter(fill_letter)
+			.namespace(namespace_from(namespace));
+		Ok(builder)
+	}
+
+	/// Runs the previewed operation with the current fields' contents.
+	fn preview(&self) -> Result<Report> {
+		let processor = self.processor()?.build().unwrap();
+		if self.deciphering {
+			processor.decipher()
+		} else {
+			processor.cipher()
+		}
+	}
+
+	/// Renders the key's matrix representation, one row per line.
+	fn matrix_view(&self) -> Result<Vec<Line<'static>>> {
+		let matrix = self.processor()?.build().unwrap().key_matrix()?;
+
+		Ok(matrix
+			.row_iter()
+			.map(|row| {
+				Line::from(row
+					.iter()
+					.map(|v| format!("{v:>4}"))
+					.collect::<String>())
+			})
+			.collect())
+	}
+
+	/// Handles a key press, returning `false` if the interface must be closed.
+	fn on_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+		match code {
+			KeyCode::Esc => return false,
+			KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return false,
+			KeyCode::Char('t') if modifiers.contains(KeyModifiers::CONTROL) => {
+				self.deciphering = !self.deciphering;
+			},
+			KeyCode::F(2) => self.deciphering = !self.deciphering,
+			KeyCode::Tab | KeyCode::Down | KeyCode::Enter => {
+				self.focus = (self.focus + 1) % FIELDS.len();
+			},
+			KeyCode::BackTab | KeyCode::Up => {
+				self.focus = (self.focus + FIELDS.len() - 1) % FIELDS.len();
+			},
+			KeyCode::Backspace => {
+				self.fields[self.focus].pop();
+			},
+			KeyCode::Char(c) => self.fields[self.focus].push(c),
+			_ => (),
+		}
+
+		true
+	}
+
+	/// Draws the whole interface.
+	fn draw(&self, frame: &mut Frame) {
+		let [main, result, help] = Layout::vertical([
+			Constraint::Min(FIELDS.len() as u16 * 3),
+			Constraint::Length(5),
+			Constraint::Length(1),
+		]).areas(frame.area());
+		let [fields, matrix] = Layout::horizontal([
+			Constraint::Percentage(60),
+			Constraint::Percentage(40),
+		]).areas(main);
+
+		self.draw_fields(frame, fields);
+
+		// the key's matrix view
+		let (lines, style) = match self.matrix_view() {
+			Ok(lines) => (lines, Style::default()),
+			Err(e) => (vec![Line::from(i18n::tr_error(&e))], Style::default().fg(Color::Red)),
+		};
+		frame.render_widget(
+			Paragraph::new(lines)
+				.style(style)
+				.wrap(Wrap { trim: false })
+				.block(Block::default().borders(Borders::ALL).title("Key matrix")),
+			matrix
+		);
+
+		// the live preview of the operation
+		let title = if self.deciphering { "Deciphered text" } else { "Ciphered text" };
+		let preview = match self.preview() {
+			Ok(report) => {
+				let mut line = vec![Span::styled(report.result_txt, Style::default().fg(Color::Blue))];
+				if report.filled {
+					line.push(Span::styled(" (filled)", Style::default().fg(Color::Yellow)));
+				}
+				Paragraph::new(Line::from(line))
+			},
+			Err(e) => Paragraph::new(i18n::tr_error(&e)).style(Style::default().fg(Color::Red)),
+		};
+		frame.render_widget(
+			preview
+				.wrap(Wrap { trim: false })
+				.block(Block::default().borders(Borders::ALL).title(title)),
+			result
+		);
+
+		frame.render_widget(
+			Paragraph::new(
+				"tab/shift-tab: switch field  ctrl-t/F2: toggle cipher/decipher  esc: quit"
+			).style(Style::default().add_modifier(Modifier::DIM)),
+			help
+		);
+	}
+
+	/// Draws the editable fields, highlighting the focused one.
+	fn draw_fields(&self, frame: &mut Frame, area: Rect) {
+		let areas = Layout::vertical([Constraint::Length(3); FIELDS.len()]).split(area);
+
+		for (i, (label, content)) in FIELDS.iter().zip(&self.fields).enumerate() {
+			let mut block = Block::default().borders(Borders::ALL).title(*label);
+			if i == self.focus {
+				block = block.border_style(Style::default().fg(Color::Yellow));
+				frame.set_cursor_position((
+					areas[i].x + 1 + content.chars().count() as u16,
+					areas[i].y + 1,
+				));
+			}
+			frame.render_widget(Paragraph::new(content.as_str()).block(block), areas[i]);
+		}
+	}
+}
+
+/// Runs the full-screen interface, previewing the `cipher` or `decipher`
+/// result while the key, namespace and source text are typed.
+pub fn run() -> Result<()> {
+	let mut terminal = ratatui::init();
+	let result = event_loop(&mut terminal);
+	ratatui::restore();
+
+	result
+}
+
+/// Draws the interface and processes the terminal events until it is closed.
+fn event_loop(terminal: &mut DefaultTerminal) -> Result<()> {
+	let mut app = App::default();
+
+	loop {
+		terminal.draw(|frame| app.draw(frame)).map_err(|e| e.to_string())?;
+
+		if let Event::Key(key) = event::read().map_err(|e| e.to_string())? {
+			if key.kind == KeyEventKind::Press && !app.on_key(key.code, key.modifiers) {
+				return Ok(());
+			}
+		}
+	}
+}
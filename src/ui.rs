@@ -1,23 +1,411 @@
-use std::error::Error;
+use std::env;
+use std::io::{self, IsTerminal as _};
+use std::path::Path;
+
+use colored::Colorize as _;
+use tracing::Level;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::i18n::{self, Msg};
+use crate::analysis::{self, Analysis};
+use crate::completions;
+use crate::config;
+use crate::crack::Candidate;
+use crate::input::ColorChoice;
+use crate::profile;
+use crate::process::{Batch, Comparison, DEFAULT_NAMESPACE, NAMESPACE_PRESETS, Report, ReportStyle};
+
+/// Enables or disables the styling of the output. With [`ColorChoice::Auto`],
+/// the output is styled only if it goes to a terminal and the `NO_COLOR`
+/// environment variable is not set.
+pub fn set_color(choice: ColorChoice) {
+	let enabled = match choice {
+		ColorChoice::Always => true,
+		ColorChoice::Never => false,
+		ColorChoice::Auto => {
+			env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+				&& io::stderr().is_terminal()
+		},
+	};
+
+	// legacy windows consoles print the escape sequences literally unless the
+	// virtual terminal processing is enabled; if it cannot be, the output is
+	// left unstyled
+	#[cfg(windows)]
+	let enabled = enabled && colored::control::set_virtual_terminal(true).is_ok();
+
+	colored::control::set_override(enabled);
+}
+
+/// Installs the logger printing the diagnostics of the processes to stderr.
+/// Only warnings are printed by default, debug logs are enabled with a
+/// `verbosity` of 1 and trace logs from 2 on. If a `profile` file is given,
+/// the stages of the processes are recorded into it until the returned guard
+/// is dropped.
+pub fn init_logging(verbosity: u8, profile: Option<&Path>) -> Result<Option<profile::Guard>> {
+	let level = match verbosity {
+		0 => Level::WARN,
+		1 => Level::DEBUG,
+		_ => Level::TRACE,
+	};
+
+	let logger = tracing_subscriber::fmt::layer()
+		.with_writer(io::stderr)
+		.with_ansi(colored::control::SHOULD_COLORIZE.should_colorize())
+		.with_target(false)
+		.without_time()
+		.with_filter(LevelFilter::from_level(level));
+	let (profiler, guard) = match profile.map(profile::layer).transpose()? {
+		Some((profiler, guard)) => (Some(profiler), Some(guard)),
+		None => (None, None),
+	};
+
+	tracing_subscriber::registry().with(logger).with(profiler).init();
+	Ok(guard)
+}
 
 /// Prints the results of the `cipher` or `decipher` processes.
 #[macro_export]
 macro_rules! report_msg {
     ($($fmt:tt)+) => {
 		eprint!("{}", colored::Colorize::bold(
-				colored::Colorize::green("Report result"))
+				colored::Colorize::green($crate::i18n::tr($crate::i18n::Msg::ReportResult)))
 		);
 		eprintln!("{}", colored::Colorize::bold(": "));
 		eprintln!("{}", &*format!($($fmt)+));
 	};
 }
 
+/// Prints only the given result `text` to stdout, ending it with a line
+/// terminator unless it already has one.
+pub fn print_raw(text: &str) {
+	if text.ends_with('\n') {
+		print!("{text}");
+	} else {
+		println!("{text}");
+	}
+}
+
+/// Prints the given `report` with the fields of the given `style`. The fill
+/// letters appended to the source text are highlighted after it. A `plain`
+/// report is printed without any styling or decoration, so it remains
+/// readable by screen readers and plain-text tools. If the result text was
+/// `written_to` a file, its path is shown instead.
+pub fn print_report(report: &Report, style: ReportStyle, plain: bool, written_to: Option<&Path>) {
+	// a result text written to a file is not dumped again
+	let fields = report.fields(style)
+		.into_iter()
+		.map(|(label, value)| match (label, written_to) {
+			(Msg::ResultText, Some(path)) => (Msg::WrittenTo, path.display().to_string()),
+			_ => (label, value),
+		});
+	if plain {
+		let lines: Vec<_> = fields.map(|(label, value)| format!("{}: {value}", i18n::tr(label))).collect();
+		eprintln!("{}", lines.join("\n"));
+		return;
+	}
+
+	let padding = report.fill_letter
+		.map(|fill| fill.to_string().repeat(report.padding.len()))
+		.unwrap_or_default();
+	let lines = fields
+		.map(|(label, value)| {
+			let value = match label {
+				Msg::SourceText if !padding.is_empty() => format!("{value}{}", padding.yellow().underline()),
+				_ => value,
+			};
+			let label = match label {
+				Msg::ResultText | Msg::WrittenTo => i18n::tr(label).blue(),
+				_ => i18n::tr(label).yellow(),
+			};
+			format!("  {label}: {value}")
+		})
+		.collect::<Vec<_>>();
+	report_msg!["{}", lines.join("\n")];
+}
+
+/// Prints the blocks of the given `report` that could not be processed,
+/// along with why.
+pub fn print_failures(report: &Report) {
+	eprintln!("{}{}", i18n::tr(Msg::FailedBlocks).red().bold(), ":".bold());
+	for failure in &report.failed_blocks {
+		eprintln!("  {} {}: {}", i18n::tr(Msg::Block).yellow(), failure.index, failure.reason);
+	}
+}
+
+/// Prints the source and result texts of the given `report` aligned character
+/// by character and split into blocks of the key's dimension. The characters
+/// added by filling the source text are highlighted, and marked beneath so they
+/// remain visible without styling.
+pub fn print_diff(report: &Report) {
+	// the line terminators kept out of the process are left out of the rows
+	let unbroken = |text: &str| text.chars().filter(|c| !['\r', '\n'].contains(c)).collect::<Vec<_>>();
+	let result = unbroken(&report.result_txt);
+	let dimension = report.key_dimension.max(1);
+	let mut source = unbroken(&report.source_txt.to_uppercase());
+	let source_len = source.len();
+	if let Some(fill) = report.fill_letter.filter(|_| report.filled) {
+		source.resize(result.len(), fill);
+	}
+
+	// every row is rendered with a block separator between the blocks
+	let row = |chars: &mut dyn Iterator<Item = String>| {
+		chars
+			.enumerate()
+			.map(|(i, c)| if i > 0 && i % dimension == 0 { format!(" {c}") } else { c })
+			.collect::<String>()
+	};
+	let source_row = row(&mut source.iter().enumerate().map(|(i, c)| {
+		if i >= source_len { c.to_string().yellow().underline().to_string() } else { c.to_string() }
+	}));
+	let result_row = row(&mut result.iter().map(char::to_string));
+	let padding_row = row(&mut (0..source.len()).map(|i| {
+		if i >= source_len { "^".to_owned() } else { " ".to_owned() }
+	}));
+
+	let labels = [Msg::SourceText, Msg::ResultText, Msg::Padding].map(i18n::tr);
+	let width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+	eprintln!("{}{}", i18n::tr(Msg::Diff).green().bold(), ":".bold());
+	eprintln!("  {:width$}  {source_row}", labels[0].yellow());
+	eprintln!("  {:width$}  {result_row}", labels[1].blue());
+	if source.len() > source_len {
+		eprintln!("  {:width$}  {}", labels[2].yellow(), padding_row.trim_end());
+	}
+}
+
+/// Prints a fixed-width table with the computations performed over every
+/// block of the report: its letters and their indices, the product vector and
+/// the reduced indices, along with the letters they stand for.
+pub fn print_dump(report: &Report) {
+	let join = |values: Vec<String>| values.join(" ");
+	let rows: Vec<[String; 6]> = report.blocks
+		.iter()
+		.enumerate()
+		.map(|(i, block)| [
+			(i + 1).to_string(),
+			block.input.clone(),
+			join(block.input_indices.iter().map(|n| format!("{n:>2}")).collect()),
+			join(block.product.iter().map(|n| format!("{n:>5}")).collect()),
+			join(block.output_indices.iter().map(|n| format!("{n:>2}")).collect()),
+			block.output.clone(),
+		])
+		.collect();
+
+	let header = [Msg::Block, Msg::Text, Msg::Indices, Msg::Product, Msg::Reduced, Msg::Text]
+		.map(|msg| i18n::tr(msg).to_owned());
+	let mut widths = header.clone().map(|h| h.chars().count());
+	for row in &rows {
+		for (width, cell) in widths.iter_mut().zip(row) {
+			*width = (*width).max(cell.chars().count());
+		}
+	}
+	let line = |cells: &[String; 6]| {
+		cells
+			.iter()
+			.zip(widths)
+			.map(|(cell, width)| format!("{cell:>width$}"))
+			.collect::<Vec<_>>()
+			.join(" | ")
+	};
+
+	eprintln!("{}{}", i18n::tr(Msg::Dump).green().bold(), ":".bold());
+	eprintln!("  {}", line(&header).yellow());
+	for row in &rows {
+		eprintln!("  {}", line(row));
+	}
+}
+
+/// Prints the table of the blocks of the given `report`, as rendered by
+/// [`Report::block_table`].
+pub fn print_block_table(report: &Report) {
+	eprintln!("{}{}", i18n::tr(Msg::BlockTable).green().bold(), ":".bold());
+	for line in report.block_table().lines() {
+		eprintln!("  {line}");
+	}
+}
+
+/// Prints the result text of the given `report` line by line, with the index
+/// of every character in the given `namespace` aligned beneath it. Characters
+/// out of the namespace are left without an index.
+pub fn print_annotation(report: &Report, namespace: &[char]) {
+	let width = namespace.len().saturating_sub(1).to_string().len();
+
+	eprintln!("{}{}", i18n::tr(Msg::Annotation).green().bold(), ":".bold());
+	for line in report.result_txt.lines() {
+		let (chars, indices): (Vec<_>, Vec<_>) = line
+			.chars()
+			.map(|c| {
+				let index = namespace.iter().position(|&n| n == c).map(|i| i.to_string()).unwrap_or_default();
+				(format!("{c:>width$}"), format!("{index:>width$}"))
+			})
+			.unzip();
+		eprintln!("  {}", chars.join(" "));
+		eprintln!("  {}", indices.join(" ").trim_end().yellow());
+	}
+}
+
+/// Prints the given `comparison` of two texts. The first difference is
+/// located by its position and, if a `block_size` is given, by its block too;
+/// both counted from 1.
+pub fn print_comparison(comparison: &Comparison, block_size: Option<usize>) {
+	let difference = match comparison.first_difference {
+		Some(i) => {
+			let position = format!("{} {}", i18n::tr(Msg::Position), i + 1);
+			match block_size.filter(|&size| size > 0) {
+				Some(size) => format!("{position}, {} {}",
+					i18n::tr(Msg::Block).to_lowercase(),
+					i / size + 1
+				),
+				None => position,
+			}
+		},
+		None => i18n::tr(Msg::Identical).to_owned(),
+	};
+
+	eprintln!("{}{}", i18n::tr(Msg::Comparison).green().bold(), ":".bold());
+	eprintln!("  {}: {difference}", i18n::tr(Msg::FirstDifference).yellow());
+	eprintln!("  {}: {:.2}% ({}/{})",
+		i18n::tr(Msg::Match).yellow(),
+		comparison.percentage(),
+		comparison.matching,
+		comparison.length
+	);
+}
+
+/// Prints what the running binary supports: its version and target, the
+/// optional features it was compiled with, the namespace presets along with
+/// their length, and the paths it reads.
+pub fn print_info() {
+	let features: Vec<&str> = [
+		("url", cfg!(feature = "url")),
+		("async", cfg!(feature = "async")),
+		("profiling", cfg!(feature = "profiling")),
+		("serde", cfg!(feature = "serde")),
+		("rayon", cfg!(feature = "rayon")),
+	]
+		.into_iter()
+		.filter_map(|(name, enabled)| enabled.then_some(name))
+		.collect();
+	let features = if features.is_empty() { "none".to_owned() } else { features.join(", ") };
+
+	println!("{} {} ({} {})",
+		completions::BIN_NAME.green().bold(),
+		env!("CARGO_PKG_VERSION"),
+		env::consts::OS,
+		env::consts::ARCH
+	);
+	println!("  {}: {features}", "Features".yellow());
+	println!("  {}:", "Namespace presets".yellow());
+	for (name, preset) in NAMESPACE_PRESETS {
+		let len = preset.map_or(DEFAULT_NAMESPACE.len(), |ns| ns.chars().count());
+		println!("    {name:<14}{len} characters, keys of any size");
+	}
+	let config = match config::default_path() {
+		Some(path) if path.exists() => path.display().to_string(),
+		Some(path) => format!("none, {} does not exist", path.display()),
+		None => "none, no configuration directory is known".to_owned(),
+	};
+	println!("  {}: {config}", "Config file".yellow());
+	println!("  {}: not supported", "Keystore".yellow());
+}
+
+/// Prints one row per processed item to stdout, holding the item's id, its
+/// result text, whether it was filled and the error it failed with, if any.
+/// The fields are separated by the given `delimiter`.
+pub fn print_table(items: &[(String, &Result<Report>)], delimiter: u8) -> Result<()> {
+	let mut writer = csv::WriterBuilder::new()
+		.delimiter(delimiter)
+		.from_writer(io::stdout());
+
+	writer.write_record(["source", "result", "filled", "error"])
+		.map_err(|e| e.to_string())?;
+	for (id, outcome) in items {
+		let record = match outcome {
+			Ok(report) => [id, &report.result_txt, &report.filled.to_string(), ""],
+			Err(e) => [id, "", "", &i18n::tr_error(e)],
+		};
+		writer.write_record(record).map_err(|e| e.to_string())?;
+	}
+
+	writer.flush().map_err(|e| e.to_string().into())
+}
+
+/// Prints every item of the given `batch`, as its input followed by its result
+/// or error, and the summary of them all.
+pub fn print_batch(batch: &Batch) {
+	for (i, item) in batch.items.iter().enumerate() {
+		let outcome = match (&item.report, &item.error) {
+			(Some(report), _) => report.result_txt.green(),
+			(None, error) => error.as_deref().unwrap_or_default().red(),
+		};
+		println!("{:>3}. {} {} {outcome}", i + 1, item.input, "->".bold());
+	}
+
+	let summary = &batch.summary;
+	println!("{}{} {} {}, {} {}, {} {}",
+		i18n::tr(Msg::Summary).cyan().bold(),
+		":".bold(),
+		summary.processed, i18n::tr(Msg::Processed),
+		summary.failed, i18n::tr(Msg::Failed),
+		summary.filled, i18n::tr(Msg::FilledCount)
+	);
+}
+
+/// Prints the given frequency `analysis` as tables of the characters and
+/// n-grams, with their count and share of the text.
+pub fn print_analysis(analysis: &Analysis) {
+	println!("{}: {}", "Length".yellow(), analysis.length);
+	println!("{}: {:.4} (english {:.4})",
+		"Index of coincidence".yellow(),
+		analysis.index_of_coincidence,
+		analysis::ENGLISH_IC
+	);
+
+	let tables = [
+		("Characters".to_owned(), &analysis.characters, analysis.length),
+		(format!("{}-grams", analysis.n), &analysis.ngrams, analysis.length.saturating_sub(analysis.n - 1)),
+	];
+	for (title, counts, total) in tables {
+		println!("{}:", title.yellow());
+		for frequency in counts {
+			let share = frequency.count as f64 * 100.0 / total.max(1) as f64;
+			println!("  {}  {:>6}  {share:>6.2}%", frequency.text, frequency.count);
+		}
+	}
+}
+
+/// Prints the given `candidates` of a key search, ranked, with their key, score
+/// and the text they decipher into.
+pub fn print_candidates(candidates: &[Candidate]) {
+	for (i, candidate) in candidates.iter().enumerate() {
+		println!("{:>3}. {}  {:>9.2}  {}",
+			i + 1,
+			candidate.key.green().bold(),
+			candidate.score,
+			candidate.plaintext
+		);
+	}
+}
+
+/// Prints the lines explaining an error, as given by [`process::explain`].
+pub fn print_explanation(lines: &[String]) {
+	if lines.is_empty() {
+		return;
+	}
+
+	eprintln!("{}{}", i18n::tr(Msg::Explanation).yellow().bold(), ":".bold());
+	for line in lines {
+		eprintln!("  {line}");
+	}
+}
+
 /// Prints any possible error catched from the `cipher` or `decipher` processes.
-pub fn print_error(err: impl Error) {
-	use colored::Colorize as _;
+pub fn print_error(err: Error) {
 	eprintln!("{}{}{}",
-		"Error".red().bold(),
+		i18n::tr(Msg::Error).red().bold(),
 		": ".bold(),
-		err.to_string().bold()
+		i18n::tr_error(&err).bold()
 	);
 }
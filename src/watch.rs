@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use tracing::{debug, warn};
+
+use crate::error::Result;
+use crate::{i18n, interrupt};
+
+/// Time between two looks at the watched files.
+pub const INTERVAL: Duration = Duration::from_millis(500);
+
+/// Watches the file at `path`, or the files right inside the directory at
+/// `path`, handing every one of them to `on_change` at first and then again
+/// whenever it is modified, until interrupted. The errors of `on_change` are
+/// only warned about, so a half saved file does not end the watch.
+pub fn run(path: &Path, mut on_change: impl FnMut(&Path) -> Result<()>) -> Result<()> {
+	let mut seen: HashMap<PathBuf, SystemTime> = HashMap::new();
+	while !interrupt::interrupted() {
+		for file in files(path)? {
+			// a file removed since it was listed is picked up if it comes back
+			let Ok(modified) = fs::metadata(&file).and_then(|meta| meta.modified()) else {
+				continue;
+			};
+			if seen.get(&file) == Some(&modified) {
+				continue;
+			}
+
+			debug!(file = %file.display(), "the watched file changed");
+			seen.insert(file.clone(), modified);
+			if let Err(e) = on_change(&file) {
+				warn!("'{}' cannot be processed: {}", file.display(), i18n::tr_error(&e));
+			}
+		}
+		thread::sleep(INTERVAL);
+	}
+
+	Ok(())
+}
+
+/// Lists the file at `path`, or the files right inside the directory at
+/// `path`, sorted by their paths.
+fn files(path: &Path) -> Result<Vec<PathBuf>> {
+	if !path.is_dir() {
+		return Ok(vec![path.to_owned()]);
+	}
+
+	let entries = fs::read_dir(path)
+		.map_err(|e| format!("the directory '{}' cannot be watched: {e}", path.display()))?;
+	let mut files: Vec<_> = entries
+		.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+		.filter(|path| path.is_file())
+		.collect();
+	files.sort();
+	Ok(files)
+}
@@ -0,0 +1,155 @@
+use std::io::{self, BufRead, Write};
+use std::num::NonZeroUsize;
+
+use colored::Colorize as _;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::terminal;
+
+use crate::error::{Error, Result};
+use crate::input::{Command, KeyOpts, OutputOpts, SourceOpts};
+use crate::process::NAMESPACE_PRESETS;
+
+/// Runs a guided prompt flow that asks for the information needed to perform
+/// a `cipher` or `decipher` operation, turning the answers into a [`Command`].
+pub fn run() -> Result<Command> {
+	let stdin = io::stdin();
+	let mut input = stdin.lock();
+
+	eprintln!("{}", "Hill cipher wizard".green().bold());
+	eprintln!("Answer the following questions (press enter to skip the optional ones).\n");
+
+	let operation = choose(&mut input, "Operation", &["cipher", "decipher"])?;
+	let key = ask(&mut input, "Key", true)?.unwrap();
+
+	// the fill letter is only mandatory for the cipher operation
+	let ciphering = operation == 0;
+	let fill_letter = loop {
+		match ask(&mut input, "Fill letter", ciphering)? {
+			Some(fill) if fill.chars().count() == 1 => break fill.chars().next(),
+			Some(_) => eprintln!("{}", "  the fill letter must be a single character".red()),
+			None => break None,
+		}
+	};
+
+	// picking either one of the presets or a custom namespace
+	let mut options: Vec<_> = NAMESPACE_PRESETS.iter().map(|(name, _)| *name).collect();
+	options.push("custom");
+	let chosen = choose(&mut input, "Namespace", &options)?;
+	let namespace = match NAMESPACE_PRESETS.get(chosen) {
+		Some((_, preset)) => preset.map(str::to_owned),
+		None => ask(&mut input, "Custom namespace", true)?,
+	};
+
+	let source = ask(&mut input, "Source text", true)?.unwrap();
+	let key_opts = KeyOpts { key: Some(key), ..Default::default() };
+	let source = SourceOpts { source: vec![source], ..Default::default() };
+
+	Ok(if ciphering {
+		Command::Cipher {
+			key: key_opts,
+			source,
+			fill_letter,
+			namespace,
+			iterations: NonZeroUsize::MIN,
+			output: OutputOpts::default(),
+		}
+	} else {
+		Command::Decipher {
+			key: key_opts,
+			source,
+			fill_letter,
+			namespace,
+			iterations: NonZeroUsize::MIN,
+			output: OutputOpts::default(),
+		}
+	})
+}
+
+/// Prompts for a single line of text. Empty answers are asked again if the
+/// answer is `required`, otherwise `None` is returned.
+fn ask(input: &mut impl BufRead, label: &str, required: bool) -> Result<Option<String>> {
+	loop {
+		eprint!("{} {}{} ", "?".yellow().bold(), label.bold(), ":".bold());
+		io::stderr().flush().ok();
+
+		let mut line = String::new();
+		if input.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+			return Err("the wizard was interrupted before all answers were given".into());
+		}
+
+		// only the line terminator is removed, since surrounding spaces can be
+		// meaningful inside a key, a namespace or a source text
+		let answer = line.trim_end_matches(['\n', '\r']);
+		if !answer.is_empty() {
+			return Ok(Some(answer.to_owned()));
+		}
+		if !required {
+			return Ok(None);
+		}
+	}
+}
+
+/// Prompts for a secret, like a key, without echoing what is typed, asking
+/// again while the answer is empty.
+pub fn ask_hidden(label: &str) -> Result<String> {
+	loop {
+		eprint!("{} {}{} ", "?".yellow().bold(), label.bold(), ":".bold());
+		io::stderr().flush().ok();
+
+		terminal::enable_raw_mode().map_err(|e| e.to_string())?;
+		let answer = read_hidden();
+		terminal::disable_raw_mode().map_err(|e| e.to_string())?;
+		eprintln!();
+
+		match answer.map_err(|e| e.to_string())? {
+			Some(answer) if answer.is_empty() => continue,
+			Some(answer) => return Ok(answer),
+			None => return Err(Error::Interrupted),
+		}
+	}
+}
+
+/// Reads the keys typed until enter is pressed, or `None` if the prompt is
+/// left with Ctrl-C or escape. The terminal must be in raw mode.
+fn read_hidden() -> io::Result<Option<String>> {
+	let mut answer = String::new();
+	loop {
+		let Event::Key(key) = event::read()? else { continue };
+		if key.kind != KeyEventKind::Press {
+			continue;
+		}
+
+		match key.code {
+			KeyCode::Enter => return Ok(Some(answer)),
+			KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+			KeyCode::Esc => return Ok(None),
+			KeyCode::Char(c) => answer.push(c),
+			KeyCode::Backspace => {
+				answer.pop();
+			},
+			_ => (),
+		}
+	}
+}
+
+/// Prompts for one of the given `options`, either by its number or by its
+/// name, returning the index of the chosen one.
+fn choose(input: &mut impl BufRead, label: &str, options: &[&str]) -> Result<usize> {
+	for (i, option) in options.iter().enumerate() {
+		eprintln!("  {} {}", format!("{})", i + 1).blue(), option);
+	}
+
+	loop {
+		let answer = ask(input, label, true)?.unwrap();
+		let answer = answer.trim();
+		let chosen = match answer.parse::<usize>() {
+			Ok(n) => n.checked_sub(1).filter(|&i| i < options.len()),
+			Err(_) => options.iter().position(|o| o.eq_ignore_ascii_case(answer)),
+		};
+
+		match chosen {
+			Some(i) => return Ok(i),
+			None => eprintln!("{}", "  unknown option, try again".red()),
+		}
+	}
+}